@@ -0,0 +1,186 @@
+//! Opt-in approximate conversion of years and months into [`time::Duration`].
+//!
+//! The crate's top-level functions reject years and months, because they have no
+//! fixed length. Some producers send `P1M` when they mean "about 30 days". For those,
+//! [`Approximate`] converts years and months using a [`Convention`] picked by the
+//! caller: [`Days30`], [`Gregorian`], or a custom one.
+//!
+//! Serialization is unchanged: values are written with days, hours, minutes and
+//! seconds, exactly like the crate's `serialize`.
+//!
+//! ```
+//! use iso8601_duration_serde::approximate::{Approximate, Gregorian};
+//! use serde::{Deserialize, Serialize};
+//! use time::Duration;
+//!
+//! # #[cfg(feature = "serde")]
+//! #[derive(Serialize, Deserialize)]
+//! struct Feed {
+//!     #[serde(with = "Approximate::<Gregorian>")]
+//!     retention: Duration,
+//! }
+//!
+//! let month = Approximate::<Gregorian>::parse("P1M").unwrap();
+//! assert_eq!(month, Duration::seconds(2_629_746));
+//! ```
+
+use core::marker::PhantomData;
+
+#[cfg(feature = "serde")]
+use serde::{Deserializer, Serializer};
+use time::Duration;
+use time_core::convert::*;
+
+use crate::components::Components;
+use crate::error::ParseError;
+
+/// How long a year and a month are, in seconds.
+///
+/// Implement this on your own type for a custom ratio:
+///
+/// ```
+/// use iso8601_duration_serde::approximate::{Approximate, Convention};
+/// use time::Duration;
+///
+/// /// Banking months of 30 days and years of 360 days.
+/// struct Banking;
+///
+/// impl Convention for Banking {
+///     const YEAR_SECONDS: u32 = 360 * 86_400;
+///     const MONTH_SECONDS: u32 = 30 * 86_400;
+/// }
+///
+/// assert_eq!(Approximate::<Banking>::parse("P1Y"), Ok(Duration::days(360)));
+/// ```
+pub trait Convention {
+    /// The length of a year, in seconds.
+    const YEAR_SECONDS: u32;
+    /// The length of a month, in seconds.
+    const MONTH_SECONDS: u32;
+}
+
+/// Months of 30 days and years of 365 days.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Days30;
+
+impl Convention for Days30 {
+    const YEAR_SECONDS: u32 = 365 * Second::per_t::<u32>(Day);
+    const MONTH_SECONDS: u32 = 30 * Second::per_t::<u32>(Day);
+}
+
+/// The average Gregorian year of 365.2425 days, and a twelfth of it as the month
+/// (30.436875 days).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Gregorian;
+
+impl Convention for Gregorian {
+    const YEAR_SECONDS: u32 = 31_556_952;
+    const MONTH_SECONDS: u32 = 2_629_746;
+}
+
+/// Parses, serializes and deserializes a [`time::Duration`], converting years and
+/// months with the convention `C`.
+///
+/// Use it with `#[serde(with = "Approximate::<Gregorian>")]`.
+pub struct Approximate<C>(PhantomData<C>);
+
+impl<C: Convention> Approximate<C> {
+    /// Parse a [`time::Duration`] from its ISO 8601 representation, converting years
+    /// and months with the convention `C`.
+    #[inline]
+    pub fn parse(input: &str) -> Result<Duration, ParseError> {
+        crate::parse::parse(input).and_then(from_components::<C>)
+    }
+
+    /// Serialize a [`time::Duration`] using the well-known ISO 8601 format.
+    ///
+    /// This is the same as [`crate::serialize`]; years and months are never written.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::serialize(duration, serializer)
+    }
+
+    /// Deserialize a [`time::Duration`] from its ISO 8601 representation, converting
+    /// years and months with the convention `C`.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Duration, D::Error> {
+        crate::deserialize_with(deserializer, from_components::<C>)
+    }
+}
+
+fn from_components<C: Convention>(components: Components) -> Result<Duration, ParseError> {
+    crate::from_nanoseconds(components.nanoseconds_with(C::YEAR_SECONDS, C::MONTH_SECONDS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conventions() {
+        assert_eq!(Approximate::<Days30>::parse("P1M"), Ok(Duration::days(30)));
+        assert_eq!(Approximate::<Days30>::parse("P1Y"), Ok(Duration::days(365)));
+        assert_eq!(
+            Approximate::<Gregorian>::parse("P1Y"),
+            Ok(Duration::seconds(31_556_952))
+        );
+        assert_eq!(
+            Approximate::<Gregorian>::parse("P1M"),
+            Ok(Duration::days(30) + Duration::seconds(37_746))
+        );
+        assert_eq!(
+            Approximate::<Gregorian>::parse("P12M"),
+            Approximate::<Gregorian>::parse("P1Y")
+        );
+
+        // The default functions still reject calendar units.
+        assert_eq!(crate::parse("P1M"), Err(ParseError::CalendarUnitNotAllowed));
+    }
+
+    #[test]
+    fn test_combined_and_fractional() {
+        assert_eq!(
+            Approximate::<Days30>::parse("-P1Y2M3DT4H"),
+            Ok(-(Duration::days(365 + 60 + 3) + Duration::hours(4)))
+        );
+        assert_eq!(
+            Approximate::<Days30>::parse("P0.5M"),
+            Ok(Duration::days(15))
+        );
+        assert_eq!(
+            Approximate::<Gregorian>::parse("P0.000000001Y"),
+            Ok(Duration::nanoseconds(31_556_952))
+        );
+        assert_eq!(Approximate::<Days30>::parse("PT1H"), Ok(Duration::hours(1)));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(
+            Approximate::<Gregorian>::parse("P18446744073709551615Y"),
+            Err(ParseError::Overflow)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "Approximate::<Days30>")]
+            duration: Duration,
+        }
+
+        let json = r#"{"duration":"P1M"}"#;
+        let deserialized: TestStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.duration, Duration::days(30));
+        assert_eq!(
+            serde_json::to_string(&deserialized).unwrap(),
+            r#"{"duration":"P30D"}"#
+        );
+    }
+}
@@ -0,0 +1,321 @@
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime};
+use time_core::convert::*;
+
+use crate::calendar::CalendarDuration;
+use crate::error::CalendarError;
+
+/// What to do when adding months lands on a day that does not exist, such as
+/// January 31 plus one month.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MonthEnd {
+    /// Use the last day of the month instead: January 31 plus `P1M` is February 28
+    /// (or 29).
+    #[default]
+    Clamp,
+    /// Carry the extra days into the next month: January 31 plus `P1M` is March 3
+    /// (or 2).
+    Overflow,
+    /// Fail with [`CalendarError::DayOutOfRange`].
+    Error,
+}
+
+/// Checked arithmetic between [`CalendarDuration`] and the `time` crate's date and
+/// datetime types.
+///
+/// Years and months are applied first, together, with the [`MonthEnd`] rule deciding
+/// what happens when the day does not exist in the resulting month. Weeks and days
+/// are then added as calendar days, and finally the exact time part. Datetimes with
+/// an offset keep their offset, so a day is always 24 hours.
+///
+/// ```
+/// use iso8601_duration_serde::{AddCalendar, CalendarDuration, MonthEnd};
+/// use time::macros::date;
+///
+/// let one_month = CalendarDuration::from_months(1);
+/// let start = date!(2024-01-31);
+/// assert_eq!(start.checked_add_calendar(&one_month, MonthEnd::Clamp), Ok(date!(2024-02-29)));
+/// assert_eq!(start.checked_add_calendar(&one_month, MonthEnd::Overflow), Ok(date!(2024-03-02)));
+/// assert!(start.checked_add_calendar(&one_month, MonthEnd::Error).is_err());
+/// ```
+pub trait AddCalendar: Sized {
+    /// Adds `duration`, failing if the result is out of range.
+    fn checked_add_calendar(
+        self,
+        duration: &CalendarDuration,
+        month_end: MonthEnd,
+    ) -> Result<Self, CalendarError>;
+
+    /// Subtracts `duration`, failing if the result is out of range.
+    ///
+    /// This is the same as adding the negated duration.
+    fn checked_sub_calendar(
+        self,
+        duration: &CalendarDuration,
+        month_end: MonthEnd,
+    ) -> Result<Self, CalendarError> {
+        self.checked_add_calendar(&-*duration, month_end)
+    }
+}
+
+/// A date has no time of day, so the time part must be a whole number of days, such
+/// as `PT48H`. Otherwise [`CalendarError::PartialDay`] is returned.
+impl AddCalendar for Date {
+    fn checked_add_calendar(
+        self,
+        duration: &CalendarDuration,
+        month_end: MonthEnd,
+    ) -> Result<Self, CalendarError> {
+        let time = duration.time();
+        if time.whole_seconds() % Second::per_t::<i64>(Day) != 0 || time.subsec_nanoseconds() != 0 {
+            return Err(CalendarError::PartialDay);
+        }
+
+        let date = add_nominal(self, duration, month_end)?;
+        add_days(date, time.whole_days())
+    }
+}
+
+impl AddCalendar for PrimitiveDateTime {
+    fn checked_add_calendar(
+        self,
+        duration: &CalendarDuration,
+        month_end: MonthEnd,
+    ) -> Result<Self, CalendarError> {
+        let date = add_nominal(self.date(), duration, month_end)?;
+        self.replace_date(date)
+            .checked_add(duration.time())
+            .ok_or(CalendarError::Overflow)
+    }
+}
+
+impl AddCalendar for OffsetDateTime {
+    fn checked_add_calendar(
+        self,
+        duration: &CalendarDuration,
+        month_end: MonthEnd,
+    ) -> Result<Self, CalendarError> {
+        let date = add_nominal(self.date(), duration, month_end)?;
+        self.replace_date(date)
+            .checked_add(duration.time())
+            .ok_or(CalendarError::Overflow)
+    }
+}
+
+/// Adds the years, months, weeks and days of `duration` to `date`.
+fn add_nominal(
+    date: Date,
+    duration: &CalendarDuration,
+    month_end: MonthEnd,
+) -> Result<Date, CalendarError> {
+    let months = duration
+        .years()
+        .checked_mul(12)
+        .and_then(|months| months.checked_add(duration.months()))
+        .ok_or(CalendarError::Overflow)?;
+    let days = duration
+        .weeks()
+        .checked_mul(7)
+        .and_then(|days| days.checked_add(duration.days()))
+        .ok_or(CalendarError::Overflow)?;
+
+    add_days(add_months(date, months, month_end)?, days)
+}
+
+pub(crate) fn add_months(
+    date: Date,
+    months: i64,
+    month_end: MonthEnd,
+) -> Result<Date, CalendarError> {
+    if months == 0 {
+        return Ok(date);
+    }
+
+    let (year, month, day) = date.to_calendar_date();
+    let index = (i64::from(year) * 12 + i64::from(month as u8 - 1))
+        .checked_add(months)
+        .ok_or(CalendarError::Overflow)?;
+    let year = i32::try_from(index.div_euclid(12)).map_err(|_| CalendarError::Overflow)?;
+    let month = Month::try_from(index.rem_euclid(12) as u8 + 1).expect("month is in range");
+
+    let length = month.length(year);
+    if day <= length {
+        return Date::from_calendar_date(year, month, day).map_err(|_| CalendarError::Overflow);
+    }
+
+    let last =
+        Date::from_calendar_date(year, month, length).map_err(|_| CalendarError::Overflow)?;
+    match month_end {
+        MonthEnd::Clamp => Ok(last),
+        MonthEnd::Overflow => add_days(last, i64::from(day - length)),
+        MonthEnd::Error => Err(CalendarError::DayOutOfRange { year, month, day }),
+    }
+}
+
+pub(crate) fn add_days(date: Date, days: i64) -> Result<Date, CalendarError> {
+    i64::from(date.to_julian_day())
+        .checked_add(days)
+        .and_then(|day| i32::try_from(day).ok())
+        .and_then(|day| Date::from_julian_day(day).ok())
+        .ok_or(CalendarError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Duration;
+    use time::macros::{date, datetime};
+
+    use super::*;
+
+    #[test]
+    fn test_month_end() {
+        let one_month = CalendarDuration::from_months(1);
+        for (start, month_end, expected) in [
+            (
+                date!(2023 - 01 - 31),
+                MonthEnd::Clamp,
+                Ok(date!(2023 - 02 - 28)),
+            ),
+            (
+                date!(2024 - 01 - 31),
+                MonthEnd::Clamp,
+                Ok(date!(2024 - 02 - 29)),
+            ),
+            (
+                date!(2023 - 01 - 31),
+                MonthEnd::Overflow,
+                Ok(date!(2023 - 03 - 03)),
+            ),
+            (
+                date!(2024 - 01 - 31),
+                MonthEnd::Overflow,
+                Ok(date!(2024 - 03 - 02)),
+            ),
+            (
+                date!(2023 - 01 - 31),
+                MonthEnd::Error,
+                Err(CalendarError::DayOutOfRange {
+                    year: 2023,
+                    month: Month::February,
+                    day: 31,
+                }),
+            ),
+            (
+                date!(2024 - 03 - 31),
+                MonthEnd::Error,
+                Err(CalendarError::DayOutOfRange {
+                    year: 2024,
+                    month: Month::April,
+                    day: 31,
+                }),
+            ),
+            (
+                date!(2023 - 01 - 28),
+                MonthEnd::Error,
+                Ok(date!(2023 - 02 - 28)),
+            ),
+        ] {
+            assert_eq!(
+                start.checked_add_calendar(&one_month, month_end),
+                expected,
+                "{start} {month_end:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_years_and_months_are_applied_together() {
+        let duration: CalendarDuration = "P1Y1M".parse().unwrap();
+        assert_eq!(
+            date!(2023 - 01 - 31).checked_add_calendar(&duration, MonthEnd::Clamp),
+            Ok(date!(2024 - 02 - 29))
+        );
+
+        let leap_day = date!(2024 - 02 - 29);
+        let one_year = CalendarDuration::from_years(1);
+        assert_eq!(
+            leap_day.checked_add_calendar(&one_year, MonthEnd::Clamp),
+            Ok(date!(2025 - 02 - 28))
+        );
+        assert_eq!(
+            leap_day.checked_add_calendar(&one_year, MonthEnd::Overflow),
+            Ok(date!(2025 - 03 - 01))
+        );
+    }
+
+    #[test]
+    fn test_sub() {
+        let duration = CalendarDuration::new(0, 1, 2, 3, Duration::ZERO).unwrap();
+        assert_eq!(
+            date!(2024 - 03 - 31).checked_sub_calendar(&duration, MonthEnd::Clamp),
+            Ok(date!(2024 - 02 - 12))
+        );
+        assert_eq!(
+            date!(2024 - 01 - 15).checked_add_calendar(&-duration, MonthEnd::Clamp),
+            date!(2024 - 01 - 15).checked_sub_calendar(&duration, MonthEnd::Clamp)
+        );
+        assert_eq!(
+            date!(2024 - 01 - 01)
+                .checked_sub_calendar(&CalendarDuration::from_years(1), MonthEnd::Clamp),
+            Ok(date!(2023 - 01 - 01))
+        );
+    }
+
+    #[test]
+    fn test_datetimes() {
+        let duration: CalendarDuration = "P1MT36H".parse().unwrap();
+        assert_eq!(
+            datetime!(2024-01-31 12:00).checked_add_calendar(&duration, MonthEnd::Clamp),
+            Ok(datetime!(2024-03-02 00:00))
+        );
+        assert_eq!(
+            datetime!(2024-01-31 12:00 +02:00).checked_add_calendar(&duration, MonthEnd::Clamp),
+            Ok(datetime!(2024-03-02 00:00 +02:00))
+        );
+        assert_eq!(
+            datetime!(2024-03-02 00:00 UTC).checked_sub_calendar(&duration, MonthEnd::Clamp),
+            Ok(datetime!(2024-01-31 12:00 UTC))
+        );
+    }
+
+    #[test]
+    fn test_date_with_time_part() {
+        let two_days: CalendarDuration = "PT48H".parse().unwrap();
+        assert_eq!(
+            date!(2024 - 02 - 28).checked_add_calendar(&two_days, MonthEnd::Clamp),
+            Ok(date!(2024 - 03 - 01))
+        );
+
+        let half_day: CalendarDuration = "PT12H".parse().unwrap();
+        assert_eq!(
+            date!(2024 - 02 - 28).checked_add_calendar(&half_day, MonthEnd::Clamp),
+            Err(CalendarError::PartialDay)
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        for duration in [
+            CalendarDuration::from_years(i64::MAX),
+            CalendarDuration::from_years(10_000),
+            CalendarDuration::from_months(-i64::MAX),
+            CalendarDuration::from_days(i64::MAX),
+            CalendarDuration::from_weeks(i64::MAX / 2),
+        ] {
+            assert_eq!(
+                date!(2024 - 01 - 01).checked_add_calendar(&duration, MonthEnd::Clamp),
+                Err(CalendarError::Overflow),
+                "{duration}"
+            );
+        }
+
+        assert_eq!(
+            Date::MAX.checked_add_calendar(&CalendarDuration::from_days(1), MonthEnd::Clamp),
+            Err(CalendarError::Overflow)
+        );
+        assert_eq!(
+            date!(2024 - 01 - 01)
+                .checked_sub_calendar(&CalendarDuration::from_months(-i64::MAX), MonthEnd::Clamp),
+            Err(CalendarError::Overflow)
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Serialize and deserialize fixed-size arrays of [`time::Duration`] using the ISO
+//! 8601 format.
+//!
+//! Arrays are written as tuples, matching serde's own representation of arrays.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Window {
+//!     #[serde(with = "iso8601_duration_serde::array")]
+//!     bounds: [Duration; 2],
+//! }
+//! ```
+
+use core::fmt;
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserializer, Serializer};
+use time::Duration;
+
+use crate::IsoDuration;
+
+/// Serialize an array of [`time::Duration`] as a tuple of ISO 8601 strings.
+#[inline]
+pub fn serialize<S: Serializer, const N: usize>(
+    durations: &[Duration; N],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(N)?;
+    for duration in durations {
+        tuple.serialize_element(&IsoDuration(*duration))?;
+    }
+    tuple.end()
+}
+
+/// Deserialize an array of [`time::Duration`] from a tuple of ISO 8601 strings.
+#[inline]
+pub fn deserialize<'a, D: Deserializer<'a>, const N: usize>(
+    deserializer: D,
+) -> Result<[Duration; N], D::Error> {
+    deserializer.deserialize_tuple(N, ArrayVisitor)
+}
+
+struct ArrayVisitor<const N: usize>;
+
+impl<'a, const N: usize> Visitor<'a> for ArrayVisitor<N> {
+    type Value = [Duration; N];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an array of {N} ISO 8601 durations")
+    }
+
+    fn visit_seq<A: SeqAccess<'a>>(self, mut seq: A) -> Result<[Duration; N], A::Error> {
+        let mut durations = [Duration::ZERO; N];
+        for (i, duration) in durations.iter_mut().enumerate() {
+            let IsoDuration(element) = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+            *duration = element;
+        }
+
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+
+        Ok(durations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+    struct TestStruct {
+        #[serde(with = "super")]
+        durations: [Duration; 3],
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let test_struct = TestStruct {
+            durations: [
+                Duration::ZERO,
+                Duration::hours(1),
+                -Duration::microseconds(250),
+            ],
+        };
+
+        let json = serde_json::to_string(&test_struct).unwrap();
+        assert_eq!(json, r#"{"durations":["PT0S","PT1H","-PT0.00025S"]}"#);
+
+        let deserialized: TestStruct = serde_json::from_str(&json).unwrap();
+        assert_eq!(test_struct, deserialized);
+    }
+
+    #[test]
+    fn test_deserialize_wrong_length_is_error() {
+        let json = r#"{"durations":["PT1S","PT2S"]}"#;
+        assert!(serde_json::from_str::<TestStruct>(json).is_err());
+
+        let json = r#"{"durations":["PT1S","PT2S","PT3S","PT4S"]}"#;
+        assert!(serde_json::from_str::<TestStruct>(json).is_err());
+    }
+}
@@ -0,0 +1,522 @@
+use core::fmt;
+use core::ops::Neg;
+use core::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use time::Duration;
+
+use crate::components::{Components, Decimal};
+use crate::error::ParseError;
+use crate::parse::ParseOptions;
+
+/// A calendar-aware duration that keeps years, months, weeks and days as written.
+///
+/// Unlike [`time::Duration`], the nominal components are not converted into a number
+/// of seconds: a month has no fixed length, so `P1M` stays one month and is never
+/// turned into `P30D`. Only the time part (hours, minutes and seconds) is exact and
+/// stored as a [`time::Duration`].
+///
+/// Every component has the same sign. A negative duration such as `-P1M` has
+/// negative components and is written with a leading minus sign. No component is
+/// `i64::MIN` and the time part is never [`Duration::MIN`], so every duration can be
+/// negated.
+///
+/// Weeks are kept apart from days, and may be combined with other components as in
+/// ISO 8601-2 (`P1W2D`). The nominal components are written back exactly as they
+/// were parsed. The time part is written from its exact value, so `PT90M` becomes
+/// `PT1H30M`, but hours are never carried into days.
+///
+/// ```
+/// use iso8601_duration_serde::CalendarDuration;
+/// use time::Duration;
+///
+/// let period: CalendarDuration = "P1Y2MT36H".parse().unwrap();
+/// assert_eq!(period.years(), 1);
+/// assert_eq!(period.months(), 2);
+/// assert_eq!(period.time(), Duration::hours(36));
+/// assert_eq!(period.to_string(), "P1Y2MT36H");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CalendarDuration {
+    years: i64,
+    months: i64,
+    weeks: i64,
+    days: i64,
+    time: Duration,
+}
+
+impl CalendarDuration {
+    /// The empty duration, written as `PT0S`.
+    pub const ZERO: Self = CalendarDuration {
+        years: 0,
+        months: 0,
+        weeks: 0,
+        days: 0,
+        time: Duration::ZERO,
+    };
+
+    /// Creates a duration from its components.
+    ///
+    /// Fails with [`ParseError::MixedSigns`] if some components are negative and
+    /// others positive, and with [`ParseError::Overflow`] if a component cannot be
+    /// negated, such as `i64::MIN` years.
+    pub fn new(
+        years: i64,
+        months: i64,
+        weeks: i64,
+        days: i64,
+        time: Duration,
+    ) -> Result<Self, ParseError> {
+        let signs = [
+            years.signum(),
+            months.signum(),
+            weeks.signum(),
+            days.signum(),
+            if time.is_negative() {
+                -1
+            } else {
+                i64::from(time.is_positive())
+            },
+        ];
+        if signs.contains(&1) && signs.contains(&-1) {
+            return Err(ParseError::MixedSigns);
+        }
+        if [years, months, weeks, days].contains(&i64::MIN) || time.checked_neg().is_none() {
+            return Err(ParseError::Overflow);
+        }
+
+        Ok(CalendarDuration {
+            years,
+            months,
+            weeks,
+            days,
+            time,
+        })
+    }
+
+    /// A duration of `years` years.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `years` is `i64::MIN`. See
+    /// [`checked_from_years`](Self::checked_from_years) for a version that does not
+    /// panic.
+    pub const fn from_years(years: i64) -> Self {
+        match CalendarDuration::checked_from_years(years) {
+            Some(duration) => duration,
+            None => panic!("years cannot be i64::MIN"),
+        }
+    }
+
+    /// A duration of `years` years, or `None` if `years` is `i64::MIN`.
+    pub const fn checked_from_years(years: i64) -> Option<Self> {
+        if years == i64::MIN {
+            return None;
+        }
+        Some(CalendarDuration {
+            years,
+            ..CalendarDuration::ZERO
+        })
+    }
+
+    /// A duration of `months` months.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `months` is `i64::MIN`. See
+    /// [`checked_from_months`](Self::checked_from_months) for a version that does not
+    /// panic.
+    pub const fn from_months(months: i64) -> Self {
+        match CalendarDuration::checked_from_months(months) {
+            Some(duration) => duration,
+            None => panic!("months cannot be i64::MIN"),
+        }
+    }
+
+    /// A duration of `months` months, or `None` if `months` is `i64::MIN`.
+    pub const fn checked_from_months(months: i64) -> Option<Self> {
+        if months == i64::MIN {
+            return None;
+        }
+        Some(CalendarDuration {
+            months,
+            ..CalendarDuration::ZERO
+        })
+    }
+
+    /// A duration of `weeks` weeks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weeks` is `i64::MIN`. See
+    /// [`checked_from_weeks`](Self::checked_from_weeks) for a version that does not
+    /// panic.
+    pub const fn from_weeks(weeks: i64) -> Self {
+        match CalendarDuration::checked_from_weeks(weeks) {
+            Some(duration) => duration,
+            None => panic!("weeks cannot be i64::MIN"),
+        }
+    }
+
+    /// A duration of `weeks` weeks, or `None` if `weeks` is `i64::MIN`.
+    pub const fn checked_from_weeks(weeks: i64) -> Option<Self> {
+        if weeks == i64::MIN {
+            return None;
+        }
+        Some(CalendarDuration {
+            weeks,
+            ..CalendarDuration::ZERO
+        })
+    }
+
+    /// A duration of `days` days.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `days` is `i64::MIN`. See
+    /// [`checked_from_days`](Self::checked_from_days) for a version that does not
+    /// panic.
+    pub const fn from_days(days: i64) -> Self {
+        match CalendarDuration::checked_from_days(days) {
+            Some(duration) => duration,
+            None => panic!("days cannot be i64::MIN"),
+        }
+    }
+
+    /// A duration of `days` days, or `None` if `days` is `i64::MIN`.
+    pub const fn checked_from_days(days: i64) -> Option<Self> {
+        if days == i64::MIN {
+            return None;
+        }
+        Some(CalendarDuration {
+            days,
+            ..CalendarDuration::ZERO
+        })
+    }
+
+    /// The number of years.
+    pub const fn years(&self) -> i64 {
+        self.years
+    }
+
+    /// The number of months.
+    pub const fn months(&self) -> i64 {
+        self.months
+    }
+
+    /// The number of weeks.
+    pub const fn weeks(&self) -> i64 {
+        self.weeks
+    }
+
+    /// The number of days.
+    pub const fn days(&self) -> i64 {
+        self.days
+    }
+
+    /// The exact time part: hours, minutes and seconds.
+    pub const fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Whether every component is zero.
+    pub fn is_zero(&self) -> bool {
+        *self == CalendarDuration::ZERO
+    }
+
+    /// Whether the duration is negative.
+    pub fn is_negative(&self) -> bool {
+        self.years < 0
+            || self.months < 0
+            || self.weeks < 0
+            || self.days < 0
+            || self.time.is_negative()
+    }
+
+    /// Multiplies every component by `rhs`, returning `None` on overflow.
+    pub fn checked_mul(&self, rhs: i64) -> Option<Self> {
+        let time = self.time.whole_nanoseconds().checked_mul(i128::from(rhs))?;
+        CalendarDuration::new(
+            self.years.checked_mul(rhs)?,
+            self.months.checked_mul(rhs)?,
+            self.weeks.checked_mul(rhs)?,
+            self.days.checked_mul(rhs)?,
+            crate::from_nanoseconds(time).ok()?,
+        )
+        .ok()
+    }
+}
+
+impl TryFrom<Duration> for CalendarDuration {
+    type Error = ParseError;
+
+    /// A duration with only a time part.
+    ///
+    /// Fails with [`ParseError::Overflow`] if `time` cannot be negated, which is only
+    /// the case when its whole seconds are `i64::MIN`, as in [`Duration::MIN`].
+    fn try_from(time: Duration) -> Result<Self, ParseError> {
+        CalendarDuration::new(0, 0, 0, 0, time)
+    }
+}
+
+impl Neg for CalendarDuration {
+    type Output = Self;
+
+    /// Flips the sign of every component. This never overflows, since the
+    /// constructors reject components that cannot be negated.
+    fn neg(self) -> Self {
+        CalendarDuration {
+            years: -self.years,
+            months: -self.months,
+            weeks: -self.weeks,
+            days: -self.days,
+            time: -self.time,
+        }
+    }
+}
+
+impl fmt::Display for CalendarDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        to_components(self).fmt(f)
+    }
+}
+
+impl FromStr for CalendarDuration {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        crate::parse::parse_with(s, PARSE_OPTIONS).and_then(from_components)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for CalendarDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&to_components(self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> Deserialize<'a> for CalendarDuration {
+    fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::deserialize_with_options(deserializer, PARSE_OPTIONS, from_components)
+    }
+}
+
+/// Weeks are kept apart from days, so `P1W2D` is written and has to be read back.
+const PARSE_OPTIONS: ParseOptions = ParseOptions {
+    combined_weeks: true,
+    ..ParseOptions::DEFAULT
+};
+
+pub(crate) fn to_components(duration: &CalendarDuration) -> Components {
+    let time = duration.time;
+    Components {
+        negative: duration.is_negative(),
+        years: Decimal::whole(duration.years.unsigned_abs()),
+        months: Decimal::whole(duration.months.unsigned_abs()),
+        weeks: Decimal::whole(duration.weeks.unsigned_abs()),
+        days: Decimal::whole(duration.days.unsigned_abs()),
+        ..Components::hms(
+            false,
+            time.whole_seconds().unsigned_abs(),
+            time.subsec_nanoseconds().unsigned_abs(),
+        )
+    }
+}
+
+pub(crate) fn from_components(components: Components) -> Result<CalendarDuration, ParseError> {
+    let nominal = [
+        components.years,
+        components.months,
+        components.weeks,
+        components.days,
+    ];
+    if nominal.iter().any(|value| value.nanoseconds != 0) {
+        return Err(ParseError::FractionalCalendarUnit);
+    }
+
+    let [years, months, weeks, days] = nominal.map(|value| {
+        let value = i64::try_from(value.whole).map_err(|_| ParseError::Overflow)?;
+        Ok(if components.negative { -value } else { value })
+    });
+
+    let time = crate::from_components(Components {
+        negative: components.negative,
+        hours: components.hours,
+        minutes: components.minutes,
+        seconds: components.seconds,
+        ..Components::default()
+    })?;
+
+    CalendarDuration::new(years?, months?, weeks?, days?, time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_keeps_nominal_components() {
+        for input in [
+            "P1M",
+            "P1Y",
+            "P2W",
+            "P1W2D",
+            "P1Y2WT3H",
+            "P30D",
+            "P1Y2M3DT4H5M6.5S",
+            "-P1M",
+            "-P1Y2DT1H",
+            "PT36H",
+            "PT0S",
+        ] {
+            let duration: CalendarDuration = input.parse().unwrap();
+            assert_eq!(duration.to_string(), input);
+        }
+
+        assert_eq!(
+            "P1M".parse::<CalendarDuration>(),
+            Ok(CalendarDuration::from_months(1))
+        );
+        assert_eq!(
+            "-P1Y".parse::<CalendarDuration>(),
+            Ok(CalendarDuration::from_years(-1))
+        );
+        assert_eq!(
+            "P0001-02-03T04:05:06.5"
+                .parse::<CalendarDuration>()
+                .map(|duration| duration.to_string()),
+            Ok("P1Y2M3DT4H5M6.5S".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_time_part_is_exact() {
+        let duration: CalendarDuration = "P1DT1.5H".parse().unwrap();
+        assert_eq!(duration.days(), 1);
+        assert_eq!(duration.time(), Duration::minutes(90));
+        assert_eq!(duration.to_string(), "P1DT1H30M");
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(
+            "P1.5M".parse::<CalendarDuration>(),
+            Err(ParseError::FractionalCalendarUnit)
+        );
+        assert_eq!(
+            "P9223372036854775808Y".parse::<CalendarDuration>(),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            CalendarDuration::new(1, -1, 0, 0, Duration::ZERO),
+            Err(ParseError::MixedSigns)
+        );
+        assert_eq!(
+            CalendarDuration::new(0, 0, 0, 1, Duration::nanoseconds(-1)),
+            Err(ParseError::MixedSigns)
+        );
+    }
+
+    #[test]
+    fn test_sign() {
+        let duration = CalendarDuration::new(-1, -2, 0, -3, Duration::hours(-4)).unwrap();
+        assert!(duration.is_negative());
+        assert_eq!(duration.to_string(), "-P1Y2M3DT4H");
+        assert_eq!((-duration).to_string(), "P1Y2M3DT4H");
+        assert!(!CalendarDuration::ZERO.is_negative());
+        assert!(CalendarDuration::ZERO.is_zero());
+    }
+
+    #[test]
+    fn test_checked_mul() {
+        let duration: CalendarDuration = "P1Y2WT1.5S".parse().unwrap();
+        assert_eq!(
+            duration.checked_mul(3).map(|duration| duration.to_string()),
+            Some("P3Y6WT4.5S".to_owned())
+        );
+        assert_eq!(
+            duration
+                .checked_mul(-1)
+                .map(|duration| duration.to_string()),
+            Some("-P1Y2WT1.5S".to_owned())
+        );
+        assert_eq!(duration.checked_mul(0), Some(CalendarDuration::ZERO));
+        assert_eq!(duration.checked_mul(i64::MAX), None);
+        assert_eq!(CalendarDuration::from_years(1 << 62).checked_mul(-2), None);
+    }
+
+    #[test]
+    fn test_negation_cannot_overflow() {
+        assert_eq!(
+            CalendarDuration::new(i64::MIN, 0, 0, 0, Duration::ZERO),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            CalendarDuration::new(0, 0, 0, 0, Duration::MIN),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            "-PT9223372036854775808S".parse::<CalendarDuration>(),
+            Err(ParseError::Overflow)
+        );
+
+        let duration = CalendarDuration::new(-i64::MAX, 0, 0, 0, -Duration::MAX).unwrap();
+        assert_eq!(
+            -duration,
+            CalendarDuration::new(i64::MAX, 0, 0, 0, Duration::MAX).unwrap()
+        );
+        assert_eq!(
+            -CalendarDuration::from_days(i64::MAX),
+            CalendarDuration::from_days(-i64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_checked_constructors() {
+        assert_eq!(CalendarDuration::checked_from_years(i64::MIN), None);
+        assert_eq!(CalendarDuration::checked_from_months(i64::MIN), None);
+        assert_eq!(CalendarDuration::checked_from_weeks(i64::MIN), None);
+        assert_eq!(CalendarDuration::checked_from_days(i64::MIN), None);
+        assert_eq!(
+            CalendarDuration::checked_from_days(-i64::MAX),
+            Some(CalendarDuration::from_days(-i64::MAX))
+        );
+
+        assert_eq!(
+            CalendarDuration::try_from(Duration::MIN),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            CalendarDuration::try_from(Duration::hours(-2)).map(|duration| duration.to_string()),
+            Ok("-PT2H".to_owned())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "years cannot be i64::MIN")]
+    fn test_from_years_rejects_min() {
+        CalendarDuration::from_years(i64::MIN);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Plan {
+            billing_period: CalendarDuration,
+        }
+
+        let json = r#"{"billing_period":"P1M"}"#;
+        let plan: Plan = serde_json::from_str(json).unwrap();
+        assert_eq!(plan.billing_period, CalendarDuration::from_months(1));
+        assert_eq!(serde_json::to_string(&plan).unwrap(), json);
+
+        let json = r#"{"billing_period":"P1.5Y"}"#;
+        assert!(serde_json::from_str::<Plan>(json).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+//! Serialize and deserialize [`chrono::TimeDelta`] using the ISO 8601 format.
+//!
+//! Requires the `chrono` feature. The output is identical to the crate's
+//! [`time::Duration`] functions.
+//!
+//! ```
+//! use chrono::TimeDelta;
+//! use serde::{Deserialize, Serialize};
+//!
+//! # #[cfg(feature = "serde")]
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "iso8601_duration_serde::chrono")]
+//!     timeout: TimeDelta,
+//! }
+//! ```
+
+use core::fmt;
+
+use ::chrono::TimeDelta;
+#[cfg(feature = "serde")]
+use serde::{Deserializer, Serializer};
+use time_core::convert::*;
+
+use crate::components::Components;
+use crate::error::ParseError;
+
+/// Parse a [`chrono::TimeDelta`] from its ISO 8601 representation.
+#[inline]
+pub fn parse(input: &str) -> Result<TimeDelta, ParseError> {
+    crate::parse::parse(input).and_then(from_components)
+}
+
+/// Format a [`chrono::TimeDelta`] using the well-known ISO 8601 format.
+#[inline]
+pub fn format(duration: &TimeDelta) -> String {
+    to_components(duration).to_string()
+}
+
+/// Display a [`chrono::TimeDelta`] using the well-known ISO 8601 format.
+#[inline]
+pub fn display(duration: &TimeDelta) -> impl fmt::Display + use<> {
+    to_components(duration)
+}
+
+/// Serialize a [`chrono::TimeDelta`] using the well-known ISO 8601 format.
+#[cfg(feature = "serde")]
+#[inline]
+pub fn serialize<S: Serializer>(delta: &TimeDelta, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&to_components(delta))
+}
+
+/// Deserialize a [`chrono::TimeDelta`] from its ISO 8601 representation.
+#[cfg(feature = "serde")]
+#[inline]
+pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<TimeDelta, D::Error> {
+    crate::deserialize_with(deserializer, from_components)
+}
+
+pub(crate) fn to_components(delta: &TimeDelta) -> Components {
+    Components::fixed(
+        *delta < TimeDelta::zero(),
+        delta.num_seconds().unsigned_abs(),
+        delta.subsec_nanos().unsigned_abs(),
+    )
+}
+
+pub(crate) fn from_components(components: Components) -> Result<TimeDelta, ParseError> {
+    let nanoseconds = components.total_nanoseconds()?;
+    let per_second = Nanosecond::per_t::<i128>(Second);
+    i64::try_from(nanoseconds.div_euclid(per_second))
+        .ok()
+        .and_then(|seconds| TimeDelta::new(seconds, nanoseconds.rem_euclid(per_second) as u32))
+        .ok_or(ParseError::Overflow)
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+    struct TestStruct {
+        #[serde(with = "super")]
+        delta: TimeDelta,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cases = [
+            (TimeDelta::zero(), "PT0S"),
+            (
+                TimeDelta::days(2) + TimeDelta::hours(3) + TimeDelta::minutes(30),
+                "P2DT3H30M",
+            ),
+            (TimeDelta::microseconds(250), "PT0.00025S"),
+            (TimeDelta::minutes(-30), "-PT30M"),
+            (TimeDelta::milliseconds(-1500), "-PT1.5S"),
+            (TimeDelta::MAX, "P106751991167DT7H12M55.807S"),
+            (TimeDelta::MIN, "-P106751991167DT7H12M55.807S"),
+        ];
+
+        for (delta, expected) in cases {
+            let test_struct = TestStruct { delta };
+            let json = serde_json::to_string(&test_struct).unwrap();
+            assert_eq!(json, format!(r#"{{"delta":"{expected}"}}"#));
+
+            let deserialized: TestStruct = serde_json::from_str(&json).unwrap();
+            assert_eq!(test_struct, deserialized);
+        }
+    }
+
+    #[test]
+    fn test_matches_time_backend() {
+        let delta = TimeDelta::days(1) + TimeDelta::nanoseconds(123_456_789);
+        let duration = time::Duration::days(1) + time::Duration::nanoseconds(123_456_789);
+
+        let mut chrono_json = Vec::new();
+        serialize(&delta, &mut serde_json::Serializer::new(&mut chrono_json)).unwrap();
+        let mut time_json = Vec::new();
+        crate::serialize(&duration, &mut serde_json::Serializer::new(&mut time_json)).unwrap();
+
+        assert_eq!(chrono_json, time_json);
+    }
+
+    #[test]
+    fn test_deserialize_overflow_is_error() {
+        let json = r#"{"delta":"P106751991167DT7H12M55.808S"}"#;
+        assert!(serde_json::from_str::<TestStruct>(json).is_err());
+    }
+}
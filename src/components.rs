@@ -0,0 +1,221 @@
+use time_core::convert::*;
+
+use crate::error::ParseError;
+
+/// A unit of an ISO 8601 duration.
+///
+/// Units are ordered from largest to smallest, which is also the order their
+/// designators must appear in: `Unit::Year < Unit::Second`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Unit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl Unit {
+    /// Every unit, from largest to smallest.
+    pub(crate) const ALL: [Unit; 7] = [
+        Unit::Year,
+        Unit::Month,
+        Unit::Week,
+        Unit::Day,
+        Unit::Hour,
+        Unit::Minute,
+        Unit::Second,
+    ];
+
+    /// The exact length of the unit in seconds, or `None` for calendar units.
+    fn seconds(self) -> Option<i128> {
+        match self {
+            Unit::Year | Unit::Month => None,
+            Unit::Week => Some(Second::per_t(Week)),
+            Unit::Day => Some(Second::per_t(Day)),
+            Unit::Hour => Some(Second::per_t(Hour)),
+            Unit::Minute => Some(Second::per_t(Minute)),
+            Unit::Second => Some(1),
+        }
+    }
+}
+
+/// An exact decimal component value: a whole part plus up to nine fractional digits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Decimal {
+    pub(crate) whole: u64,
+    pub(crate) nanoseconds: u32,
+}
+
+impl Decimal {
+    pub(crate) const fn whole(whole: u64) -> Self {
+        Decimal {
+            whole,
+            nanoseconds: 0,
+        }
+    }
+
+    pub(crate) fn is_zero(self) -> bool {
+        self.whole == 0 && self.nanoseconds == 0
+    }
+}
+
+/// The components of an ISO 8601 duration, as parsed or about to be formatted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Components {
+    /// Set when the duration has a leading minus sign, e.g. `-P1D`.
+    pub(crate) negative: bool,
+    /// The components written with their own minus sign, as in ISO 8601-2, indexed by
+    /// [`Unit`]. For example the hours in `P1DT-2H`.
+    pub(crate) negated: [bool; 7],
+    pub(crate) years: Decimal,
+    pub(crate) months: Decimal,
+    pub(crate) weeks: Decimal,
+    pub(crate) days: Decimal,
+    pub(crate) hours: Decimal,
+    pub(crate) minutes: Decimal,
+    pub(crate) seconds: Decimal,
+}
+
+impl Components {
+    /// Splits a fixed-length duration into days, hours, minutes and seconds.
+    pub(crate) fn fixed(negative: bool, seconds: u64, nanoseconds: u32) -> Self {
+        Components::split(negative, seconds, nanoseconds, Unit::Day)
+    }
+
+    /// Splits a fixed-length duration into hours, minutes and seconds, without
+    /// carrying whole days into a day component.
+    pub(crate) fn hms(negative: bool, seconds: u64, nanoseconds: u32) -> Self {
+        Components::split(negative, seconds, nanoseconds, Unit::Hour)
+    }
+
+    /// Splits a fixed-length duration into days, hours, minutes and seconds, using no
+    /// unit larger than `largest`. Weeks, months and years are never used.
+    pub(crate) fn split(negative: bool, seconds: u64, nanoseconds: u32, largest: Unit) -> Self {
+        let mut components = Components {
+            negative,
+            ..Components::default()
+        };
+
+        let mut seconds = seconds;
+        for unit in [Unit::Day, Unit::Hour, Unit::Minute] {
+            if unit >= largest {
+                let per_unit = unit.seconds().unwrap_or(1) as u64;
+                *components.get_mut(unit) = Decimal::whole(seconds / per_unit);
+                seconds %= per_unit;
+            }
+        }
+        components.seconds = Decimal {
+            whole: seconds,
+            nanoseconds,
+        };
+
+        components
+    }
+
+    /// Writes whole days as weeks when there is nothing else, e.g. `P14D` as `P2W`.
+    pub(crate) fn with_weeks(self) -> Self {
+        let per_week = Second::per_t::<u64>(Week) / Second::per_t::<u64>(Day);
+        let only_days = Components {
+            negative: self.negative,
+            days: Decimal::whole(self.days.whole),
+            ..Components::default()
+        };
+        if self != only_days || self.days.whole == 0 || !self.days.whole.is_multiple_of(per_week) {
+            return self;
+        }
+
+        Components {
+            weeks: Decimal::whole(self.days.whole / per_week),
+            days: Decimal::default(),
+            ..self
+        }
+    }
+
+    /// Whether every component is zero.
+    pub(crate) fn is_zero(&self) -> bool {
+        Unit::ALL.into_iter().all(|unit| self.get(unit).is_zero())
+    }
+
+    /// Keeps only the first `max` non-zero components, dropping the smaller ones.
+    pub(crate) fn truncate_components(mut self, max: usize) -> Self {
+        let mut remaining = max;
+        for unit in Unit::ALL {
+            let value = self.get_mut(unit);
+            if value.is_zero() {
+                continue;
+            }
+            if remaining == 0 {
+                *value = Decimal::default();
+            } else {
+                remaining -= 1;
+            }
+        }
+
+        self
+    }
+
+    pub(crate) fn get(&self, unit: Unit) -> Decimal {
+        match unit {
+            Unit::Year => self.years,
+            Unit::Month => self.months,
+            Unit::Week => self.weeks,
+            Unit::Day => self.days,
+            Unit::Hour => self.hours,
+            Unit::Minute => self.minutes,
+            Unit::Second => self.seconds,
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, unit: Unit) -> &mut Decimal {
+        match unit {
+            Unit::Year => &mut self.years,
+            Unit::Month => &mut self.months,
+            Unit::Week => &mut self.weeks,
+            Unit::Day => &mut self.days,
+            Unit::Hour => &mut self.hours,
+            Unit::Minute => &mut self.minutes,
+            Unit::Second => &mut self.seconds,
+        }
+    }
+
+    /// The exact length of the duration in nanoseconds.
+    ///
+    /// Fails if a calendar component (years or months) is present.
+    pub(crate) fn total_nanoseconds(&self) -> Result<i128, ParseError> {
+        if !self.years.is_zero() || !self.months.is_zero() {
+            return Err(ParseError::CalendarUnitNotAllowed);
+        }
+
+        Ok(self.nanoseconds_with(0, 0))
+    }
+
+    /// The length of the duration in nanoseconds, counting a year as `year` seconds
+    /// and a month as `month` seconds.
+    pub(crate) fn nanoseconds_with(&self, year: u32, month: u32) -> i128 {
+        // Even `u64::MAX` years and months of `u32::MAX` seconds each stay below
+        // `i128::MAX`.
+        let total: i128 = Unit::ALL
+            .into_iter()
+            .map(|unit| {
+                let value = self.get(unit);
+                let seconds = match unit {
+                    Unit::Year => i128::from(year),
+                    Unit::Month => i128::from(month),
+                    _ => unit.seconds().unwrap_or_default(),
+                };
+                let nanoseconds = value.whole as i128 * seconds * Nanosecond::per_t::<i128>(Second)
+                    + value.nanoseconds as i128 * seconds;
+                if self.negated[unit as usize] {
+                    -nanoseconds
+                } else {
+                    nanoseconds
+                }
+            })
+            .sum();
+
+        if self.negative { -total } else { total }
+    }
+}
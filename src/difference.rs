@@ -0,0 +1,587 @@
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime};
+
+use crate::arithmetic::{AddCalendar, MonthEnd, add_days, add_months};
+use crate::calendar::CalendarDuration;
+use crate::components::Unit;
+use crate::error::CalendarError;
+
+/// How to round the remainder below the smallest unit of a difference.
+///
+/// [`Expand`](Self::Expand) and [`HalfExpand`](Self::HalfExpand) need the end
+/// rounded away from zero to exist. Within one smallest unit of [`Date::MIN`] or
+/// [`Date::MAX`] it does not, and the difference fails with
+/// [`CalendarError::Overflow`], even when [`HalfExpand`](Self::HalfExpand) would
+/// have rounded toward zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Drop the remainder, rounding toward zero.
+    #[default]
+    Trunc,
+    /// Round away from zero whenever there is a remainder.
+    Expand,
+    /// Round to the nearest value, with ties rounded away from zero.
+    HalfExpand,
+}
+
+/// Which units a [`CalendarDifference`] is expressed in, and how it is rounded.
+///
+/// By default the difference uses every unit from years down to exact seconds,
+/// except weeks, and is not rounded.
+///
+/// ```
+/// use iso8601_duration_serde::{DifferenceOptions, RoundingMode, Unit};
+///
+/// let options = DifferenceOptions::new()
+///     .largest(Unit::Month)
+///     .smallest(Unit::Day)
+///     .rounding(RoundingMode::HalfExpand);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DifferenceOptions {
+    largest: Unit,
+    smallest: Option<Unit>,
+    rounding: RoundingMode,
+}
+
+impl DifferenceOptions {
+    /// Years down to exact seconds, without rounding.
+    pub const fn new() -> Self {
+        DifferenceOptions {
+            largest: Unit::Year,
+            smallest: None,
+            rounding: RoundingMode::Trunc,
+        }
+    }
+
+    /// The largest unit in the result. Anything larger is expressed in this unit,
+    /// e.g. `P14M` instead of `P1Y2M` for [`Unit::Month`]. Weeks are only used when
+    /// this or the smallest unit is [`Unit::Week`].
+    ///
+    /// [`Unit::Hour`] is the smallest unit that has an effect. The time part of a
+    /// [`CalendarDuration`] is exact and always written in hours, minutes and
+    /// seconds, so [`Unit::Minute`] and [`Unit::Second`] give the same result as
+    /// [`Unit::Hour`], e.g. `PT456H` for 19 days.
+    pub const fn largest(self, unit: Unit) -> Self {
+        DifferenceOptions {
+            largest: unit,
+            ..self
+        }
+    }
+
+    /// The smallest unit in the result. The remainder is rounded according to
+    /// [`rounding`](Self::rounding). Without a smallest unit, the difference is exact
+    /// down to the nanosecond.
+    ///
+    /// If this unit is larger than the largest unit, it is used as the largest unit
+    /// too.
+    pub const fn smallest(self, unit: Unit) -> Self {
+        DifferenceOptions {
+            smallest: Some(unit),
+            ..self
+        }
+    }
+
+    /// How to round the remainder below the smallest unit. Rounding away from zero
+    /// can fail near the ends of the supported date range, see [`RoundingMode`].
+    pub const fn rounding(self, mode: RoundingMode) -> Self {
+        DifferenceOptions {
+            rounding: mode,
+            ..self
+        }
+    }
+}
+
+impl Default for DifferenceOptions {
+    fn default() -> Self {
+        DifferenceOptions::new()
+    }
+}
+
+/// The calendar difference between two dates or datetimes, as a [`CalendarDuration`].
+///
+/// Adding an unrounded result to the start with [`AddCalendar`] and
+/// [`MonthEnd::Clamp`] gives back the end. A rounded result gives the end rounded to
+/// the smallest unit.
+///
+/// Datetimes with an offset are compared in the offset of the start, so a day is
+/// always 24 hours.
+///
+/// ```
+/// use iso8601_duration_serde::{CalendarDifference, DifferenceOptions};
+/// use time::macros::datetime;
+///
+/// let signup = datetime!(2023-01-15 10:00 UTC);
+/// let cancellation = datetime!(2024-03-18 14:00 UTC);
+/// let tenure = signup.until(cancellation, DifferenceOptions::new()).unwrap();
+/// assert_eq!(tenure.to_string(), "P1Y2M3DT4H");
+/// ```
+pub trait CalendarDifference: AddCalendar {
+    /// The duration from `self` to `end`. It is negative if `end` is earlier.
+    fn until(
+        self,
+        end: Self,
+        options: DifferenceOptions,
+    ) -> Result<CalendarDuration, CalendarError>;
+
+    /// The duration from `start` to `self`. It is negative if `start` is later.
+    fn since(
+        self,
+        start: Self,
+        options: DifferenceOptions,
+    ) -> Result<CalendarDuration, CalendarError> {
+        start.until(self, options)
+    }
+}
+
+impl CalendarDifference for Date {
+    fn until(
+        self,
+        end: Self,
+        options: DifferenceOptions,
+    ) -> Result<CalendarDuration, CalendarError> {
+        difference(self.midnight(), end.midnight(), options)
+    }
+}
+
+impl CalendarDifference for PrimitiveDateTime {
+    fn until(
+        self,
+        end: Self,
+        options: DifferenceOptions,
+    ) -> Result<CalendarDuration, CalendarError> {
+        difference(self, end, options)
+    }
+}
+
+impl CalendarDifference for OffsetDateTime {
+    fn until(
+        self,
+        end: Self,
+        options: DifferenceOptions,
+    ) -> Result<CalendarDuration, CalendarError> {
+        let end = end
+            .checked_to_offset(self.offset())
+            .ok_or(CalendarError::Overflow)?;
+        difference(
+            PrimitiveDateTime::new(self.date(), self.time()),
+            PrimitiveDateTime::new(end.date(), end.time()),
+            options,
+        )
+    }
+}
+
+fn difference(
+    start: PrimitiveDateTime,
+    end: PrimitiveDateTime,
+    options: DifferenceOptions,
+) -> Result<CalendarDuration, CalendarError> {
+    let largest = options
+        .largest
+        .min(options.smallest.unwrap_or(Unit::Second));
+    let exact = exact_difference(start, end, largest, options.smallest)?;
+    let Some(smallest) = options.smallest else {
+        return Ok(exact);
+    };
+
+    let truncated = truncate(&exact, smallest);
+    if truncated == exact {
+        return Ok(exact);
+    }
+
+    let sign = if end < start { -1 } else { 1 };
+    let expanded = expand(&truncated, smallest, sign);
+    let round_up = match options.rounding {
+        RoundingMode::Trunc => false,
+        RoundingMode::Expand => {
+            // The result has to add back to a representable end.
+            start.checked_add_calendar(&expanded, MonthEnd::Clamp)?;
+            true
+        }
+        RoundingMode::HalfExpand => {
+            let lower = start.checked_add_calendar(&truncated, MonthEnd::Clamp)?;
+            let upper = start.checked_add_calendar(&expanded, MonthEnd::Clamp)?;
+            (end - lower).abs() * 2 >= (upper - lower).abs()
+        }
+    };
+
+    Ok(balance(
+        if round_up { expanded } else { truncated },
+        largest,
+    ))
+}
+
+/// The unrounded difference, using units from `largest` down to nanoseconds.
+fn exact_difference(
+    start: PrimitiveDateTime,
+    end: PrimitiveDateTime,
+    largest: Unit,
+    smallest: Option<Unit>,
+) -> Result<CalendarDuration, CalendarError> {
+    if largest >= Unit::Hour {
+        return CalendarDuration::try_from(end - start).map_err(|_| CalendarError::Overflow);
+    }
+
+    // Count whole days up to the last point at the start's time of day that does not
+    // pass the end, and leave the rest to the time part.
+    let forward = end >= start;
+    let mut date = end.date();
+    if forward && end.time() < start.time() {
+        date = add_days(date, -1)?;
+    } else if !forward && end.time() > start.time() {
+        date = add_days(date, 1)?;
+    }
+    let time = end - PrimitiveDateTime::new(date, start.time());
+
+    let mut months = 0;
+    let mut base = start.date();
+    if largest <= Unit::Month {
+        let index = |date: Date| i64::from(date.year()) * 12 + i64::from(date.month() as u8);
+        months = index(date) - index(start.date());
+        base = add_months(start.date(), months, MonthEnd::Clamp)?;
+        if (forward && base > date) || (!forward && base < date) {
+            months -= if forward { 1 } else { -1 };
+            base = add_months(start.date(), months, MonthEnd::Clamp)?;
+        }
+    }
+    let mut days = i64::from(date.to_julian_day() - base.to_julian_day());
+
+    let years = if largest == Unit::Year {
+        months / 12
+    } else {
+        0
+    };
+    months -= years * 12;
+
+    let weeks = if largest == Unit::Week || smallest == Some(Unit::Week) {
+        days / 7
+    } else {
+        0
+    };
+    days -= weeks * 7;
+
+    Ok(CalendarDuration::new(years, months, weeks, days, time)
+        .expect("every component has the sign of the difference"))
+}
+
+/// Drops every component below `smallest`.
+fn truncate(duration: &CalendarDuration, smallest: Unit) -> CalendarDuration {
+    let keep = |unit: Unit, value: i64| if unit <= smallest { value } else { 0 };
+    let time = duration.time();
+    let time = match smallest {
+        Unit::Hour => Duration::hours(time.whole_hours()),
+        Unit::Minute => Duration::minutes(time.whole_minutes()),
+        Unit::Second => Duration::seconds(time.whole_seconds()),
+        Unit::Year | Unit::Month | Unit::Week | Unit::Day => Duration::ZERO,
+    };
+
+    CalendarDuration::new(
+        keep(Unit::Year, duration.years()),
+        keep(Unit::Month, duration.months()),
+        keep(Unit::Week, duration.weeks()),
+        keep(Unit::Day, duration.days()),
+        time,
+    )
+    .expect("truncating keeps the sign of every component")
+}
+
+/// Adds one `unit` away from zero.
+fn expand(duration: &CalendarDuration, unit: Unit, sign: i64) -> CalendarDuration {
+    let mut years = duration.years();
+    let mut months = duration.months();
+    let mut weeks = duration.weeks();
+    let mut days = duration.days();
+    let mut time = duration.time();
+    match unit {
+        Unit::Year => years += sign,
+        Unit::Month => months += sign,
+        Unit::Week => weeks += sign,
+        Unit::Day => days += sign,
+        Unit::Hour => time += Duration::hours(sign),
+        Unit::Minute => time += Duration::minutes(sign),
+        Unit::Second => time += Duration::seconds(sign),
+    }
+
+    CalendarDuration::new(years, months, weeks, days, time)
+        .expect("expanding keeps the sign of every component")
+}
+
+/// Carries a component that rounding filled up into the next larger unit, such as
+/// `PT24H` into `P1D`, as long as that unit is not larger than `largest`.
+fn balance(duration: CalendarDuration, largest: Unit) -> CalendarDuration {
+    let mut years = duration.years();
+    let mut months = duration.months();
+    let mut weeks = duration.weeks();
+    let mut days = duration.days();
+    let mut time = duration.time();
+
+    if largest <= Unit::Day {
+        days += time.whole_days();
+        time -= Duration::days(time.whole_days());
+    }
+    if largest == Unit::Week {
+        weeks += days / 7;
+        days %= 7;
+    }
+    if largest == Unit::Year {
+        years += months / 12;
+        months %= 12;
+    }
+
+    CalendarDuration::new(years, months, weeks, days, time)
+        .expect("balancing keeps the sign of every component")
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use super::*;
+
+    #[test]
+    fn test_until_adds_back_to_end() {
+        for (start, end) in [
+            (datetime!(2023-01-15 10:00), datetime!(2024-03-18 14:30)),
+            (datetime!(2024-01-31 00:00), datetime!(2024-02-29 00:00)),
+            (datetime!(2023-01-30 00:00), datetime!(2023-03-01 00:00)),
+            (datetime!(2024-01-01 12:00), datetime!(2024-01-02 06:00)),
+            (
+                datetime!(2024-03-31 23:59:59.5),
+                datetime!(2025-02-28 00:00),
+            ),
+            (datetime!(2024-02-29 00:00), datetime!(2024-01-31 00:00)),
+            (datetime!(2024-03-18 14:30), datetime!(2023-01-15 10:00)),
+            (datetime!(2024-01-02 06:00), datetime!(2024-01-01 12:00)),
+        ] {
+            for largest in [Unit::Year, Unit::Month, Unit::Week, Unit::Day, Unit::Hour] {
+                let options = DifferenceOptions::new().largest(largest);
+                let duration = start.until(end, options).unwrap();
+                assert_eq!(
+                    start.checked_add_calendar(&duration, MonthEnd::Clamp),
+                    Ok(end),
+                    "{start} + {duration} ({largest:?})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_until() {
+        let start = datetime!(2023-01-15 10:00);
+        let end = datetime!(2024-03-18 14:30);
+        let until = |options| start.until(end, options).unwrap().to_string();
+
+        assert_eq!(until(DifferenceOptions::new()), "P1Y2M3DT4H30M");
+        assert_eq!(
+            until(DifferenceOptions::new().largest(Unit::Month)),
+            "P14M3DT4H30M"
+        );
+        assert_eq!(
+            until(DifferenceOptions::new().largest(Unit::Day)),
+            "P428DT4H30M"
+        );
+        assert_eq!(
+            until(DifferenceOptions::new().largest(Unit::Hour)),
+            "PT10276H30M"
+        );
+
+        assert_eq!(
+            date!(2024 - 01 - 31).until(date!(2024 - 02 - 29), DifferenceOptions::new()),
+            Ok(CalendarDuration::from_months(1))
+        );
+        assert_eq!(
+            date!(2024 - 02 - 29).until(date!(2024 - 01 - 31), DifferenceOptions::new()),
+            Ok(CalendarDuration::from_days(-29))
+        );
+        assert_eq!(
+            date!(2024 - 01 - 01)
+                .until(
+                    date!(2024 - 01 - 18),
+                    DifferenceOptions::new().largest(Unit::Week)
+                )
+                .unwrap()
+                .to_string(),
+            "P2W3D"
+        );
+    }
+
+    #[test]
+    fn test_since() {
+        let options = DifferenceOptions::new();
+        let since = |end: Date, start: Date| end.since(start, options).unwrap().to_string();
+        assert_eq!(since(date!(2024 - 03 - 31), date!(2024 - 02 - 29)), "P1M2D");
+        assert_eq!(since(date!(2024 - 02 - 29), date!(2024 - 03 - 31)), "-P1M");
+    }
+
+    #[test]
+    fn test_offsets() {
+        let start = datetime!(2024-01-01 00:00 +02:00);
+        let end = datetime!(2024-01-01 00:00 UTC);
+        assert_eq!(
+            start.until(end, DifferenceOptions::new()),
+            Ok(CalendarDuration::try_from(Duration::hours(2)).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_rounding() {
+        let start = datetime!(2024-01-01 00:00);
+        let round = |end, smallest, rounding| {
+            let options = DifferenceOptions::new()
+                .smallest(smallest)
+                .rounding(rounding);
+            start.until(end, options).unwrap().to_string()
+        };
+
+        let end = datetime!(2024-01-02 13:00);
+        assert_eq!(round(end, Unit::Day, RoundingMode::Trunc), "P1D");
+        assert_eq!(round(end, Unit::Day, RoundingMode::Expand), "P2D");
+        assert_eq!(round(end, Unit::Day, RoundingMode::HalfExpand), "P2D");
+        assert_eq!(
+            round(
+                datetime!(2024-01-02 12:00),
+                Unit::Day,
+                RoundingMode::HalfExpand
+            ),
+            "P2D"
+        );
+        assert_eq!(
+            round(
+                datetime!(2024-01-02 11:59),
+                Unit::Day,
+                RoundingMode::HalfExpand
+            ),
+            "P1D"
+        );
+        assert_eq!(
+            round(
+                datetime!(2024-01-02 00:00:01),
+                Unit::Day,
+                RoundingMode::Expand
+            ),
+            "P2D"
+        );
+
+        // February 2024 has 29 days, so the 19th is past the middle.
+        let end = datetime!(2024-02-20 00:00);
+        assert_eq!(round(end, Unit::Month, RoundingMode::Trunc), "P1M");
+        assert_eq!(round(end, Unit::Month, RoundingMode::HalfExpand), "P2M");
+
+        assert_eq!(
+            round(
+                datetime!(2024-01-01 00:00:01.5),
+                Unit::Second,
+                RoundingMode::HalfExpand
+            ),
+            "PT2S"
+        );
+        assert_eq!(
+            round(
+                datetime!(2024-01-18 00:00),
+                Unit::Week,
+                RoundingMode::HalfExpand
+            ),
+            "P2W"
+        );
+
+        let end = datetime!(2023-12-30 12:00);
+        assert_eq!(round(end, Unit::Day, RoundingMode::Trunc), "-P1D");
+        assert_eq!(round(end, Unit::Day, RoundingMode::HalfExpand), "-P2D");
+    }
+
+    #[test]
+    fn test_rounding_carries_into_larger_units() {
+        let start = datetime!(2024-01-01 00:00);
+        let options = DifferenceOptions::new()
+            .smallest(Unit::Hour)
+            .rounding(RoundingMode::HalfExpand);
+        assert_eq!(
+            start.until(datetime!(2024-01-01 23:59), options),
+            Ok(CalendarDuration::from_days(1))
+        );
+
+        let options = DifferenceOptions::new()
+            .smallest(Unit::Month)
+            .rounding(RoundingMode::Expand);
+        assert_eq!(
+            start.until(datetime!(2024-12-02 00:00), options),
+            Ok(CalendarDuration::from_years(1))
+        );
+
+        let options = DifferenceOptions::new()
+            .largest(Unit::Hour)
+            .smallest(Unit::Hour)
+            .rounding(RoundingMode::HalfExpand);
+        assert_eq!(
+            start.until(datetime!(2024-01-01 23:59), options),
+            Ok(CalendarDuration::try_from(Duration::hours(24)).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_rounding_near_date_limits() {
+        let day = DifferenceOptions::new().smallest(Unit::Day);
+        for (start, end) in [
+            (datetime!(9999-12-31 00:00), datetime!(9999-12-31 01:00)),
+            (datetime!(-9999-01-01 18:00), datetime!(-9999-01-01 00:00)),
+        ] {
+            assert_eq!(start.until(end, day), Ok(CalendarDuration::ZERO));
+            for mode in [RoundingMode::Expand, RoundingMode::HalfExpand] {
+                assert_eq!(
+                    start.until(end, day.rounding(mode)),
+                    Err(CalendarError::Overflow),
+                    "{start} to {end} ({mode:?})"
+                );
+            }
+        }
+
+        let year = DifferenceOptions::new()
+            .smallest(Unit::Year)
+            .rounding(RoundingMode::Expand);
+        assert_eq!(
+            date!(9998 - 06 - 01).until(date!(9998 - 07 - 01), year),
+            Ok(CalendarDuration::from_years(1))
+        );
+        assert_eq!(
+            date!(9999 - 06 - 01).until(date!(9999 - 07 - 01), year),
+            Err(CalendarError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_largest_below_hour() {
+        let hour = DifferenceOptions::new().largest(Unit::Hour);
+        for unit in [Unit::Minute, Unit::Second] {
+            let options = DifferenceOptions::new().largest(unit);
+            let difference = date!(2024 - 01 - 01).until(date!(2024 - 01 - 20), options);
+            assert_eq!(
+                difference.map(|duration| duration.to_string()),
+                Ok("PT456H".to_owned())
+            );
+            assert_eq!(
+                difference,
+                date!(2024 - 01 - 01).until(date!(2024 - 01 - 20), hour)
+            );
+
+            let start = datetime!(2024-01-01 00:00);
+            let end = datetime!(2024-01-01 01:00:00.5);
+            assert_eq!(
+                start
+                    .until(end, options)
+                    .map(|duration| duration.to_string()),
+                Ok("PT1H0.5S".to_owned())
+            );
+            assert_eq!(start.until(end, options), start.until(end, hour));
+        }
+    }
+
+    #[test]
+    fn test_smallest_larger_than_largest() {
+        let options = DifferenceOptions::new()
+            .largest(Unit::Day)
+            .smallest(Unit::Month);
+        assert_eq!(
+            date!(2024 - 01 - 01).until(date!(2024 - 03 - 05), options),
+            Ok(CalendarDuration::from_months(2))
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use core::fmt;
+
+use time::Month;
+
+/// Error produced when a string is not a supported ISO 8601 duration, or does not fit
+/// in the target duration type.
+///
+/// Syntax errors carry the byte offset in the input at which they were detected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The duration designator `P`, or the unit designator after a number, is missing.
+    MissingDesignator { offset: usize },
+    /// A character that is not allowed at this position.
+    UnexpectedChar { offset: usize, found: char },
+    /// The input ended in the middle of a component, e.g. after a decimal point.
+    UnexpectedEnd { offset: usize },
+    /// `P` or `T` is not followed by any component.
+    MissingComponent { offset: usize },
+    /// A component appears out of order.
+    ComponentOutOfOrder { offset: usize },
+    /// A component appears more than once.
+    DuplicateComponent { offset: usize },
+    /// Hours or seconds appear before the time designator `T`, e.g. `P1H`.
+    MissingTimeDesignator { offset: usize },
+    /// A component has its own sign, e.g. `PT1H-15M`, which only the lenient parser
+    /// accepts.
+    SignedComponent { offset: usize },
+    /// Weeks are combined with other components, e.g. `P1W2D`.
+    WeeksNotAlone { offset: usize },
+    /// A component of the alternative format reaches its carry-over point, e.g. the
+    /// hours in `P0000-00-00T24:00:00`.
+    ComponentOutOfRange { offset: usize },
+    /// The date and time of an interval is not an RFC 3339 date and time, e.g.
+    /// `2024-03-01T13:00:00Z`.
+    InvalidDateTime { offset: usize },
+    /// A fraction has more than nine digits.
+    TooManyFractionDigits { offset: usize },
+    /// A component other than the last one has a fraction, e.g. `P1.5DT1H`.
+    FractionNotOnLastComponent { offset: usize },
+    /// A component other than the seconds has a fraction, which XML Schema does not
+    /// allow, e.g. `PT1.5H`.
+    FractionNotOnSeconds { offset: usize },
+    /// Years or months were given for a fixed-length duration type.
+    CalendarUnitNotAllowed,
+    /// Years, months, weeks or days have a fraction, but the target type only holds
+    /// whole calendar units.
+    FractionalCalendarUnit,
+    /// The duration is negative, but the target type cannot represent that.
+    Negative,
+    /// Some components are negative and others positive.
+    MixedSigns,
+    /// The value does not fit in the target type.
+    Overflow,
+}
+
+impl ParseError {
+    /// The byte offset in the input at which the error was detected, if any.
+    pub fn offset(&self) -> Option<usize> {
+        match *self {
+            ParseError::MissingDesignator { offset }
+            | ParseError::UnexpectedChar { offset, .. }
+            | ParseError::UnexpectedEnd { offset }
+            | ParseError::MissingComponent { offset }
+            | ParseError::ComponentOutOfOrder { offset }
+            | ParseError::DuplicateComponent { offset }
+            | ParseError::MissingTimeDesignator { offset }
+            | ParseError::SignedComponent { offset }
+            | ParseError::WeeksNotAlone { offset }
+            | ParseError::ComponentOutOfRange { offset }
+            | ParseError::InvalidDateTime { offset }
+            | ParseError::TooManyFractionDigits { offset }
+            | ParseError::FractionNotOnLastComponent { offset }
+            | ParseError::FractionNotOnSeconds { offset } => Some(offset),
+            ParseError::CalendarUnitNotAllowed
+            | ParseError::FractionalCalendarUnit
+            | ParseError::Negative
+            | ParseError::MixedSigns
+            | ParseError::Overflow => None,
+        }
+    }
+
+    /// Moves the offset `by` bytes further, for errors found in a part of a larger
+    /// input.
+    pub(crate) fn offset_by(mut self, by: usize) -> Self {
+        match &mut self {
+            ParseError::MissingDesignator { offset }
+            | ParseError::UnexpectedChar { offset, .. }
+            | ParseError::UnexpectedEnd { offset }
+            | ParseError::MissingComponent { offset }
+            | ParseError::ComponentOutOfOrder { offset }
+            | ParseError::DuplicateComponent { offset }
+            | ParseError::MissingTimeDesignator { offset }
+            | ParseError::SignedComponent { offset }
+            | ParseError::WeeksNotAlone { offset }
+            | ParseError::ComponentOutOfRange { offset }
+            | ParseError::InvalidDateTime { offset }
+            | ParseError::TooManyFractionDigits { offset }
+            | ParseError::FractionNotOnLastComponent { offset }
+            | ParseError::FractionNotOnSeconds { offset } => *offset += by,
+            ParseError::CalendarUnitNotAllowed
+            | ParseError::FractionalCalendarUnit
+            | ParseError::Negative
+            | ParseError::MixedSigns
+            | ParseError::Overflow => {}
+        }
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ParseError::MissingDesignator { .. } => f.write_str("missing designator"),
+            ParseError::UnexpectedChar { found, .. } => {
+                write!(f, "unexpected character {found:?}")
+            }
+            ParseError::UnexpectedEnd { .. } => f.write_str("unexpected end of input"),
+            ParseError::MissingComponent { .. } => f.write_str("expected at least one component"),
+            ParseError::ComponentOutOfOrder { .. } => f.write_str("component is out of order"),
+            ParseError::DuplicateComponent { .. } => f.write_str("component is repeated"),
+            ParseError::MissingTimeDesignator { .. } => {
+                f.write_str("time components must follow the `T` designator")
+            }
+            ParseError::SignedComponent { .. } => {
+                f.write_str("only the whole duration may have a sign")
+            }
+            ParseError::WeeksNotAlone { .. } => {
+                f.write_str("weeks cannot be combined with other components")
+            }
+            ParseError::ComponentOutOfRange { .. } => f.write_str("component is out of range"),
+            ParseError::InvalidDateTime { .. } => f.write_str("invalid RFC 3339 date and time"),
+            ParseError::TooManyFractionDigits { .. } => {
+                f.write_str("at most nine fractional digits are supported")
+            }
+            ParseError::FractionNotOnLastComponent { .. } => {
+                f.write_str("only the last component may have a fraction")
+            }
+            ParseError::FractionNotOnSeconds { .. } => {
+                f.write_str("only the seconds may have a fraction")
+            }
+            ParseError::CalendarUnitNotAllowed => {
+                f.write_str("years and months are not allowed in a fixed-length duration")
+            }
+            ParseError::FractionalCalendarUnit => {
+                f.write_str("years, months, weeks and days must be whole numbers")
+            }
+            ParseError::Negative => f.write_str("negative durations are not supported"),
+            ParseError::MixedSigns => f.write_str("components must all have the same sign"),
+            ParseError::Overflow => f.write_str("duration is too large"),
+        }?;
+
+        match self.offset() {
+            Some(offset) => write!(f, " at byte {offset}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Error produced when adding a [`CalendarDuration`](crate::CalendarDuration) to a
+/// date or datetime fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CalendarError {
+    /// The result is outside the range of the date or datetime type.
+    Overflow,
+    /// The day does not exist in the resulting month, and
+    /// [`MonthEnd::Error`](crate::MonthEnd::Error) was requested.
+    DayOutOfRange { year: i32, month: Month, day: u8 },
+    /// The time part is not a whole number of days, so it cannot be added to a date.
+    PartialDay,
+}
+
+impl fmt::Display for CalendarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CalendarError::Overflow => f.write_str("the result is out of range"),
+            CalendarError::DayOutOfRange { year, month, day } => {
+                write!(f, "{month} {year} has no day {day}")
+            }
+            CalendarError::PartialDay => {
+                f.write_str("a date can only be moved by a whole number of days")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CalendarError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ParseError::UnexpectedChar {
+                offset: 3,
+                found: 'D'
+            }
+            .to_string(),
+            "unexpected character 'D' at byte 3"
+        );
+        assert_eq!(
+            ParseError::CalendarUnitNotAllowed.to_string(),
+            "years and months are not allowed in a fixed-length duration"
+        );
+    }
+
+    #[test]
+    fn test_calendar_display() {
+        assert_eq!(
+            CalendarError::DayOutOfRange {
+                year: 2023,
+                month: Month::February,
+                day: 30
+            }
+            .to_string(),
+            "February 2023 has no day 30"
+        );
+    }
+
+    #[test]
+    fn test_offset() {
+        assert_eq!(ParseError::MissingComponent { offset: 1 }.offset(), Some(1));
+        assert_eq!(ParseError::Overflow.offset(), None);
+        assert_eq!(
+            ParseError::MissingComponent { offset: 1 }.offset_by(4),
+            ParseError::MissingComponent { offset: 5 }
+        );
+        assert_eq!(ParseError::Overflow.offset_by(4), ParseError::Overflow);
+    }
+}
@@ -0,0 +1,511 @@
+use core::fmt;
+
+use time::Duration;
+
+use crate::components::{Components, Decimal, Unit};
+
+/// How a duration is written when the format's zero has no components.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ZeroStyle {
+    /// `PT0S`.
+    #[default]
+    Seconds,
+    /// `P0D`.
+    Days,
+}
+
+/// Which ISO 8601 notation a duration is written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Notation {
+    /// The format with designators, e.g. `P1DT2H30M`.
+    #[default]
+    Designators,
+    /// The alternative format in the basic notation, e.g. `P00000001T023000`.
+    AlternativeBasic,
+    /// The alternative format in the extended notation, e.g. `P0000-00-01T02:30:00`.
+    AlternativeExtended,
+}
+
+/// Options for how a [`time::Duration`] is written.
+///
+/// The defaults produce exactly what [`crate::format`] and `serialize` write. Every
+/// method is a `const fn`, so options can be stored in a constant and attached to a
+/// field with [`Styled`](crate::style::Styled).
+///
+/// ```
+/// use iso8601_duration_serde::{FormatOptions, Unit};
+/// use time::Duration;
+///
+/// const JAVA: FormatOptions = FormatOptions::new().largest(Unit::Hour);
+/// assert_eq!(JAVA.format(&Duration::hours(36)), "PT36H");
+///
+/// let millis = FormatOptions::new().fraction_digits(3);
+/// assert_eq!(millis.format(&Duration::seconds(90)), "PT1M30.000S");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FormatOptions {
+    largest: Unit,
+    weeks: bool,
+    fraction_digits: Option<u8>,
+    zero: ZeroStyle,
+    max_components: Option<usize>,
+    decimal_comma: bool,
+    notation: Notation,
+}
+
+impl FormatOptions {
+    /// The options used by [`crate::format`] and `serialize`.
+    pub const fn new() -> Self {
+        FormatOptions {
+            largest: Unit::Day,
+            weeks: false,
+            fraction_digits: None,
+            zero: ZeroStyle::Seconds,
+            max_components: None,
+            decimal_comma: false,
+            notation: Notation::Designators,
+        }
+    }
+
+    /// The largest unit to write. [`Unit::Hour`] gives the Java style `PT36H`, with
+    /// no day component. Units larger than [`Unit::Day`] are the same as
+    /// [`Unit::Day`], since a [`time::Duration`] has no years or months.
+    pub const fn largest(self, unit: Unit) -> Self {
+        FormatOptions {
+            largest: unit,
+            ..self
+        }
+    }
+
+    /// Write an exact, non-zero number of weeks as `PnW`, e.g. `P2W`.
+    pub const fn weeks(self, weeks: bool) -> Self {
+        FormatOptions { weeks, ..self }
+    }
+
+    /// Write the seconds with exactly this many fractional digits, up to nine. Extra
+    /// digits are truncated. By default, as many digits as needed are written.
+    ///
+    /// This only changes how the seconds are written, not whether they are: a
+    /// duration without seconds, or whose seconds truncate to zero, has no seconds
+    /// component, so one minute is still `PT1M`. A zero duration written as seconds
+    /// gets the fixed digits, as in `PT0.000S`.
+    pub const fn fraction_digits(self, digits: u8) -> Self {
+        FormatOptions {
+            fraction_digits: Some(if digits > 9 { 9 } else { digits }),
+            ..self
+        }
+    }
+
+    /// How to write a zero duration.
+    pub const fn zero(self, zero: ZeroStyle) -> Self {
+        FormatOptions { zero, ..self }
+    }
+
+    /// Write at most this many non-zero components, dropping the smaller ones. For
+    /// example `P1DT2H3M` is written as `P1DT2H` with a maximum of two. This loses
+    /// precision.
+    pub const fn max_components(self, max: usize) -> Self {
+        FormatOptions {
+            max_components: Some(max),
+            ..self
+        }
+    }
+
+    /// Write a comma as the decimal sign, e.g. `PT0,5S`, as preferred by ISO 8601.
+    /// By default a full stop is written.
+    pub const fn decimal_comma(self, decimal_comma: bool) -> Self {
+        FormatOptions {
+            decimal_comma,
+            ..self
+        }
+    }
+
+    /// Which notation to write. The alternative format cannot write 30 days or more,
+    /// since days must stay below their carry-over point, so such durations are
+    /// written with designators instead. The options for weeks, zero and the maximum
+    /// number of components only apply to designators.
+    pub const fn notation(self, notation: Notation) -> Self {
+        FormatOptions { notation, ..self }
+    }
+
+    /// Format a [`time::Duration`] with these options.
+    pub fn format(&self, duration: &Duration) -> String {
+        self.display(duration).to_string()
+    }
+
+    /// Display a [`time::Duration`] with these options.
+    pub fn display(&self, duration: &Duration) -> impl fmt::Display + use<> {
+        Formatted {
+            components: self.components(duration),
+            options: *self,
+        }
+    }
+
+    fn components(&self, duration: &Duration) -> Components {
+        let mut components = Components::split(
+            duration.is_negative(),
+            duration.whole_seconds().unsigned_abs(),
+            duration.subsec_nanoseconds().unsigned_abs(),
+            self.largest,
+        );
+        if let Some(digits) = self.fraction_digits {
+            let unit = 10u32.pow(9 - u32::from(digits));
+            components.seconds.nanoseconds -= components.seconds.nanoseconds % unit;
+        }
+        if self.notation != Notation::Designators && fits_alternative(&components) {
+            return components;
+        }
+        if self.weeks {
+            components = components.with_weeks();
+        }
+        if let Some(max) = self.max_components {
+            components = components.truncate_components(max);
+        }
+        components
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions::new()
+    }
+}
+
+/// Components together with the options to write them with.
+pub(crate) struct Formatted {
+    pub(crate) components: Components,
+    pub(crate) options: FormatOptions,
+}
+
+impl fmt::Display for Formatted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_components(f, &self.components, &self.options)
+    }
+}
+
+/// Writes the ISO 8601 representation of the components, e.g. `P1DT2H30M0.5S`.
+///
+/// Only integer arithmetic is used, so every value is written exactly, down to
+/// nanosecond precision. Zero components are omitted, and negative durations use
+/// the common signed extension with a leading minus, e.g. `-PT30M`.
+impl fmt::Display for Components {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_components(f, self, &FormatOptions::new())
+    }
+}
+
+fn write_components(
+    f: &mut fmt::Formatter<'_>,
+    components: &Components,
+    options: &FormatOptions,
+) -> fmt::Result {
+    if options.notation != Notation::Designators && fits_alternative(components) {
+        return write_alternative(f, components, options);
+    }
+
+    if components.is_zero() {
+        // `P` alone is not a valid duration, so zero needs one explicit component.
+        return match options.zero {
+            ZeroStyle::Seconds => {
+                f.write_str("PT")?;
+                write_component(f, Decimal::default(), 'S', options)
+            }
+            ZeroStyle::Days => f.write_str("P0D"),
+        };
+    }
+
+    if components.negative {
+        f.write_str("-")?;
+    }
+
+    f.write_str("P")?;
+    for (value, designator) in [
+        (components.years, 'Y'),
+        (components.months, 'M'),
+        (components.weeks, 'W'),
+        (components.days, 'D'),
+    ] {
+        if !value.is_zero() {
+            write_component(f, value, designator, options)?;
+        }
+    }
+
+    let time = [
+        (components.hours, 'H'),
+        (components.minutes, 'M'),
+        (components.seconds, 'S'),
+    ];
+    if time.iter().all(|(value, _)| value.is_zero()) {
+        return Ok(());
+    }
+
+    f.write_str("T")?;
+    for (value, designator) in time {
+        if !value.is_zero() {
+            write_component(f, value, designator, options)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the components are below the carry-over points of the alternative format.
+fn fits_alternative(components: &Components) -> bool {
+    components.weeks.is_zero()
+        && [
+            (components.years, 10_000),
+            (components.months, 12),
+            (components.days, 30),
+            (components.hours, 24),
+            (components.minutes, 60),
+            (components.seconds, 60),
+        ]
+        .iter()
+        .all(|(value, limit)| value.whole < *limit)
+        && [
+            components.years,
+            components.months,
+            components.days,
+            components.hours,
+            components.minutes,
+        ]
+        .iter()
+        .all(|value| value.nanoseconds == 0)
+}
+
+/// Writes the components in the alternative format, e.g. `P0000-00-01T02:30:00`.
+fn write_alternative(
+    f: &mut fmt::Formatter<'_>,
+    components: &Components,
+    options: &FormatOptions,
+) -> fmt::Result {
+    let (date, time) = match options.notation {
+        Notation::AlternativeExtended => ("-", ":"),
+        _ => ("", ""),
+    };
+
+    if components.negative && !components.is_zero() {
+        f.write_str("-")?;
+    }
+    write!(
+        f,
+        "P{:04}{date}{:02}{date}{:02}T{:02}{time}{:02}{time}{:02}",
+        components.years.whole,
+        components.months.whole,
+        components.days.whole,
+        components.hours.whole,
+        components.minutes.whole,
+        components.seconds.whole,
+    )?;
+
+    let sign = if options.decimal_comma { ',' } else { '.' };
+    match options.fraction_digits {
+        Some(digits) => write_fixed_fraction(f, components.seconds.nanoseconds, digits, sign),
+        None => write_fraction(f, components.seconds.nanoseconds, sign),
+    }
+}
+
+/// Writes a component followed by its designator, e.g. `0.25S`.
+fn write_component(
+    f: &mut fmt::Formatter<'_>,
+    value: Decimal,
+    designator: char,
+    options: &FormatOptions,
+) -> fmt::Result {
+    write!(f, "{}", value.whole)?;
+    let sign = if options.decimal_comma { ',' } else { '.' };
+    match options.fraction_digits {
+        Some(digits) if designator == 'S' => {
+            write_fixed_fraction(f, value.nanoseconds, digits, sign)?
+        }
+        _ => write_fraction(f, value.nanoseconds, sign)?,
+    }
+    write!(f, "{designator}")
+}
+
+/// Writes `nanoseconds` as a decimal fraction without trailing zeros, e.g. `.00025`.
+fn write_fraction(f: &mut fmt::Formatter<'_>, nanoseconds: u32, sign: char) -> fmt::Result {
+    if nanoseconds == 0 {
+        return Ok(());
+    }
+
+    let mut digits = 9;
+    let mut fraction = nanoseconds;
+    while fraction.is_multiple_of(10) {
+        fraction /= 10;
+        digits -= 1;
+    }
+
+    write!(f, "{sign}{fraction:0digits$}")
+}
+
+/// Writes `nanoseconds` as a decimal fraction with exactly `digits` digits, e.g.
+/// `.250` for three digits.
+fn write_fixed_fraction(
+    f: &mut fmt::Formatter<'_>,
+    nanoseconds: u32,
+    digits: u8,
+    sign: char,
+) -> fmt::Result {
+    if digits == 0 {
+        return Ok(());
+    }
+
+    let fraction = nanoseconds / 10u32.pow(9 - u32::from(digits));
+    let digits = usize::from(digits);
+    write!(f, "{sign}{fraction:0digits$}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_format() {
+        for duration in [
+            Duration::ZERO,
+            Duration::hours(36),
+            Duration::weeks(2),
+            Duration::nanoseconds(-1_500),
+        ] {
+            assert_eq!(
+                FormatOptions::new().format(&duration),
+                crate::format(&duration)
+            );
+        }
+    }
+
+    #[test]
+    fn test_largest() {
+        let duration = Duration::days(1) + Duration::hours(12) + Duration::seconds(30);
+        let format = |unit| FormatOptions::new().largest(unit).format(&duration);
+        assert_eq!(format(Unit::Year), "P1DT12H30S");
+        assert_eq!(format(Unit::Day), "P1DT12H30S");
+        assert_eq!(format(Unit::Hour), "PT36H30S");
+        assert_eq!(format(Unit::Minute), "PT2160M30S");
+        assert_eq!(format(Unit::Second), "PT129630S");
+    }
+
+    #[test]
+    fn test_weeks() {
+        let options = FormatOptions::new().weeks(true);
+        assert_eq!(options.format(&Duration::weeks(3)), "P3W");
+        assert_eq!(options.format(&Duration::days(8)), "P8D");
+        assert_eq!(
+            options.largest(Unit::Hour).format(&Duration::weeks(1)),
+            "PT168H"
+        );
+    }
+
+    #[test]
+    fn test_fraction_digits() {
+        let duration = Duration::seconds(1) + Duration::nanoseconds(123_456_789);
+        let format = |digits| {
+            FormatOptions::new()
+                .fraction_digits(digits)
+                .format(&duration)
+        };
+        assert_eq!(format(0), "PT1S");
+        assert_eq!(format(3), "PT1.123S");
+        assert_eq!(format(9), "PT1.123456789S");
+        assert_eq!(format(12), "PT1.123456789S");
+    }
+
+    #[test]
+    fn test_fraction_digits_without_seconds() {
+        let options = FormatOptions::new().fraction_digits(3);
+        assert_eq!(options.format(&Duration::minutes(1)), "PT1M");
+        assert_eq!(options.format(&Duration::days(1)), "P1D");
+        assert_eq!(options.format(&Duration::minutes(90)), "PT1H30M");
+        assert_eq!(options.format(&Duration::ZERO), "PT0.000S");
+        assert_eq!(
+            options.format(&(Duration::hours(1) + Duration::nanoseconds(999))),
+            "PT1H"
+        );
+        assert_eq!(
+            options.format(&(Duration::minutes(1) + Duration::milliseconds(5))),
+            "PT1M0.005S"
+        );
+    }
+
+    #[test]
+    fn test_zero() {
+        let options = FormatOptions::new().zero(ZeroStyle::Days);
+        assert_eq!(options.format(&Duration::ZERO), "P0D");
+        assert_eq!(
+            options.format(&Duration::nanoseconds(-1)),
+            "-PT0.000000001S"
+        );
+    }
+
+    #[test]
+    fn test_decimal_comma() {
+        let options = FormatOptions::new().decimal_comma(true);
+        assert_eq!(options.format(&Duration::milliseconds(1_500)), "PT1,5S");
+        assert_eq!(options.format(&Duration::seconds(90)), "PT1M30S");
+        assert_eq!(
+            options
+                .fraction_digits(3)
+                .format(&Duration::milliseconds(-250)),
+            "-PT0,250S"
+        );
+        assert_eq!(
+            crate::parse(&options.format(&Duration::nanoseconds(1))),
+            Ok(Duration::nanoseconds(1))
+        );
+    }
+
+    #[test]
+    fn test_notation() {
+        let duration = Duration::days(1) + Duration::hours(2) + Duration::milliseconds(30_500);
+        let extended = FormatOptions::new().notation(Notation::AlternativeExtended);
+        let basic = FormatOptions::new().notation(Notation::AlternativeBasic);
+        assert_eq!(extended.format(&duration), "P0000-00-01T02:00:30.5");
+        assert_eq!(basic.format(&duration), "P00000001T020030.5");
+        assert_eq!(extended.format(&-duration), "-P0000-00-01T02:00:30.5");
+        assert_eq!(extended.format(&Duration::ZERO), "P0000-00-00T00:00:00");
+        assert_eq!(
+            basic
+                .fraction_digits(3)
+                .decimal_comma(true)
+                .format(&Duration::seconds(1)),
+            "P00000000T000001,000"
+        );
+
+        for duration in [duration, -duration, Duration::nanoseconds(1)] {
+            assert_eq!(crate::parse(&extended.format(&duration)), Ok(duration));
+            assert_eq!(crate::parse(&basic.format(&duration)), Ok(duration));
+        }
+    }
+
+    #[test]
+    fn test_notation_fallback() {
+        let extended = FormatOptions::new().notation(Notation::AlternativeExtended);
+        assert_eq!(extended.format(&Duration::days(30)), "P30D");
+        assert_eq!(
+            extended.largest(Unit::Hour).format(&Duration::hours(36)),
+            "PT36H"
+        );
+        assert_eq!(extended.weeks(true).format(&Duration::weeks(5)), "P5W");
+        assert_eq!(
+            extended.weeks(true).format(&Duration::weeks(1)),
+            "P0000-00-07T00:00:00"
+        );
+    }
+
+    #[test]
+    fn test_max_components() {
+        let duration = Duration::days(1) + Duration::hours(2) + Duration::minutes(3);
+        let format = |max| FormatOptions::new().max_components(max).format(&duration);
+        assert_eq!(format(1), "P1D");
+        assert_eq!(format(2), "P1DT2H");
+        assert_eq!(format(3), "P1DT2H3M");
+        assert_eq!(format(0), "PT0S");
+
+        let duration = -(Duration::days(1) + Duration::minutes(3));
+        assert_eq!(
+            FormatOptions::new().max_components(1).format(&duration),
+            "-P1D"
+        );
+    }
+}
@@ -0,0 +1,777 @@
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::de::{self, Visitor};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::arithmetic::{AddCalendar, MonthEnd};
+use crate::calendar::CalendarDuration;
+use crate::error::{CalendarError, ParseError};
+
+/// An ISO 8601 time interval, such as `2024-03-01T13:00:00Z/PT2H`.
+///
+/// Each form keeps what was written, so a value is displayed and serialized in the
+/// form it was parsed from. Dates and times use RFC 3339, and durations are
+/// [`CalendarDuration`]s, so `P1M` stays one month until the interval is
+/// [resolved](Interval::resolve).
+///
+/// Only these three forms are accepted. A duration on its own, such as `PT2H`, is
+/// not an interval here, since it has no position in time. The duration may be
+/// negative, as in `2024-03-01T00:00:00Z/-P1D`.
+///
+/// Dates and times that RFC 3339 cannot write, with a year outside 0 to 9999 or an
+/// offset with seconds, are displayed with an expanded year such as `-0001` and an
+/// offset such as `+01:00:30`. That is not read back, so serializing them fails.
+///
+/// ```
+/// use iso8601_duration_serde::{Interval, MonthEnd};
+/// use time::macros::datetime;
+///
+/// let interval: Interval = "2024-03-01T13:00:00Z/PT2H".parse().unwrap();
+/// assert_eq!(
+///     interval.resolve(MonthEnd::Clamp),
+///     Ok((datetime!(2024-03-01 13:00 UTC), datetime!(2024-03-01 15:00 UTC)))
+/// );
+/// assert_eq!(interval.to_string(), "2024-03-01T13:00:00Z/PT2H");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Interval {
+    /// A start and an end, e.g. `2024-03-01T13:00:00Z/2024-03-01T15:00:00Z`.
+    StartEnd(OffsetDateTime, OffsetDateTime),
+    /// A start and a duration, e.g. `2024-03-01T13:00:00Z/PT2H`.
+    StartDuration(OffsetDateTime, CalendarDuration),
+    /// A duration and an end, e.g. `PT2H/2024-03-01T15:00:00Z`.
+    DurationEnd(CalendarDuration, OffsetDateTime),
+}
+
+impl Interval {
+    /// The start and end of the interval.
+    ///
+    /// The missing end is the start plus the duration, and the missing start is the
+    /// end minus the duration, following [`AddCalendar`] with the given [`MonthEnd`]
+    /// rule.
+    pub fn resolve(
+        &self,
+        month_end: MonthEnd,
+    ) -> Result<(OffsetDateTime, OffsetDateTime), CalendarError> {
+        match *self {
+            Interval::StartEnd(start, end) => Ok((start, end)),
+            Interval::StartDuration(start, duration) => {
+                Ok((start, start.checked_add_calendar(&duration, month_end)?))
+            }
+            Interval::DurationEnd(duration, end) => {
+                Ok((end.checked_sub_calendar(&duration, month_end)?, end))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Interval::StartEnd(start, end) => {
+                write_datetime(f, start)?;
+                f.write_str("/")?;
+                write_datetime(f, end)
+            }
+            Interval::StartDuration(start, duration) => {
+                write_datetime(f, start)?;
+                write!(f, "/{duration}")
+            }
+            Interval::DurationEnd(duration, end) => {
+                write!(f, "{duration}/")?;
+                write_datetime(f, end)
+            }
+        }
+    }
+}
+
+impl FromStr for Interval {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let Some(solidus) = s.find('/') else {
+            return Err(ParseError::UnexpectedEnd { offset: s.len() });
+        };
+        let (start, end) = (&s[..solidus], &s[solidus + 1..]);
+        let end_offset = solidus + 1;
+
+        match (is_duration(start), is_duration(end)) {
+            (false, false) => Ok(Interval::StartEnd(
+                parse_datetime(start, 0)?,
+                parse_datetime(end, end_offset)?,
+            )),
+            (false, true) => Ok(Interval::StartDuration(
+                parse_datetime(start, 0)?,
+                parse_duration(end, end_offset)?,
+            )),
+            (true, false) => Ok(Interval::DurationEnd(
+                parse_duration(start, 0)?,
+                parse_datetime(end, end_offset)?,
+            )),
+            (true, true) => Err(ParseError::InvalidDateTime { offset: end_offset }),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Interval {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        check_rfc3339(self)?;
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> Deserialize<'a> for Interval {
+    fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(IntervalVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct IntervalVisitor;
+
+#[cfg(feature = "serde")]
+impl Visitor<'_> for IntervalVisitor {
+    type Value = Interval;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an ISO 8601 time interval")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Interval, E> {
+        v.parse()
+            .map_err(|err| E::custom(format_args!("invalid ISO 8601 interval {v:?}: {err}")))
+    }
+}
+
+/// An ISO 8601 repeating interval, such as `R5/2024-01-01T00:00:00Z/PT1H`.
+///
+/// `Rn` repeats the interval `n` times and `R` repeats it forever. The interval keeps
+/// its form, so a value is displayed and serialized as it was parsed.
+///
+/// ```
+/// use iso8601_duration_serde::{MonthEnd, RepeatingInterval};
+/// use time::macros::datetime;
+///
+/// let schedule: RepeatingInterval = "R3/2024-01-31T09:00:00Z/P1M".parse().unwrap();
+/// let occurrences: Vec<_> = schedule.occurrences(MonthEnd::Clamp).collect();
+/// assert_eq!(
+///     occurrences,
+///     [
+///         datetime!(2024-01-31 09:00 UTC),
+///         datetime!(2024-02-29 09:00 UTC),
+///         datetime!(2024-03-31 09:00 UTC),
+///     ]
+/// );
+/// assert_eq!(schedule.to_string(), "R3/2024-01-31T09:00:00Z/P1M");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RepeatingInterval {
+    repetitions: Option<u32>,
+    interval: Interval,
+}
+
+impl RepeatingInterval {
+    /// Repeats `interval` the given number of times, or forever if `None`.
+    pub const fn new(repetitions: Option<u32>, interval: Interval) -> Self {
+        RepeatingInterval {
+            repetitions,
+            interval,
+        }
+    }
+
+    /// The number of repetitions, or `None` if the interval repeats forever.
+    pub const fn repetitions(&self) -> Option<u32> {
+        self.repetitions
+    }
+
+    /// The interval being repeated.
+    pub const fn interval(&self) -> Interval {
+        self.interval
+    }
+
+    /// The start of each repetition.
+    ///
+    /// For `start/duration` and `start/end`, the first occurrence is the start and
+    /// each following one is one duration later. For `duration/end`, the repetitions
+    /// count back from the end, so the latest start comes first.
+    ///
+    /// Occurrence `k` is the anchor plus `k` times the duration, not the previous
+    /// occurrence plus the duration, so a monthly schedule starting on January 31 stays
+    /// on the 31st whenever the month has one. When it does not, `month_end` decides:
+    /// with [`MonthEnd::Error`] that occurrence is skipped, but still counts as a
+    /// repetition. The iterator stops early if an occurrence is out of range.
+    pub fn occurrences(&self, month_end: MonthEnd) -> Occurrences {
+        let (anchor, step, backwards) = match self.interval {
+            Interval::StartEnd(start, end) => {
+                let step = CalendarDuration::try_from(end - start)
+                    .expect("the time between two datetimes can be negated");
+                (start, step, false)
+            }
+            Interval::StartDuration(start, duration) => (start, duration, false),
+            Interval::DurationEnd(duration, end) => (end, duration, true),
+        };
+        Occurrences {
+            anchor,
+            step,
+            backwards,
+            month_end,
+            index: 0,
+            repetitions: self.repetitions,
+        }
+    }
+}
+
+impl fmt::Display for RepeatingInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.repetitions {
+            Some(repetitions) => write!(f, "R{repetitions}/{}", self.interval),
+            None => write!(f, "R/{}", self.interval),
+        }
+    }
+}
+
+impl FromStr for RepeatingInterval {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let Some(rest) = s.strip_prefix('R') else {
+            return Err(ParseError::MissingDesignator { offset: 0 });
+        };
+
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let repetitions = match digits {
+            0 => None,
+            _ => Some(rest[..digits].parse().map_err(|_| ParseError::Overflow)?),
+        };
+
+        let offset = 1 + digits;
+        match rest[digits..].chars().next() {
+            Some('/') => {}
+            Some(found) => return Err(ParseError::UnexpectedChar { offset, found }),
+            None => return Err(ParseError::UnexpectedEnd { offset }),
+        }
+
+        let interval = rest[digits + 1..]
+            .parse::<Interval>()
+            .map_err(|err| err.offset_by(offset + 1))?;
+        Ok(RepeatingInterval::new(repetitions, interval))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for RepeatingInterval {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        check_rfc3339(&self.interval)?;
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> Deserialize<'a> for RepeatingInterval {
+    fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(RepeatingIntervalVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct RepeatingIntervalVisitor;
+
+#[cfg(feature = "serde")]
+impl Visitor<'_> for RepeatingIntervalVisitor {
+    type Value = RepeatingInterval;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an ISO 8601 repeating interval")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<RepeatingInterval, E> {
+        v.parse().map_err(|err| {
+            E::custom(format_args!(
+                "invalid ISO 8601 repeating interval {v:?}: {err}"
+            ))
+        })
+    }
+}
+
+/// An iterator over the starts of each repetition of a [`RepeatingInterval`].
+///
+/// Created by [`RepeatingInterval::occurrences`].
+#[derive(Clone, Debug)]
+pub struct Occurrences {
+    anchor: OffsetDateTime,
+    step: CalendarDuration,
+    backwards: bool,
+    month_end: MonthEnd,
+    index: u32,
+    repetitions: Option<u32>,
+}
+
+impl Iterator for Occurrences {
+    type Item = OffsetDateTime;
+
+    fn next(&mut self) -> Option<OffsetDateTime> {
+        loop {
+            if self
+                .repetitions
+                .is_some_and(|repetitions| self.index >= repetitions)
+            {
+                return None;
+            }
+
+            let factor = if self.backwards {
+                -(i64::from(self.index) + 1)
+            } else {
+                i64::from(self.index)
+            };
+            self.index = self.index.checked_add(1)?;
+
+            let occurrence = self
+                .step
+                .checked_mul(factor)
+                .ok_or(CalendarError::Overflow)
+                .and_then(|offset| self.anchor.checked_add_calendar(&offset, self.month_end));
+            match occurrence {
+                Ok(occurrence) => return Some(occurrence),
+                Err(CalendarError::DayOutOfRange { .. }) => continue,
+                Err(_) => {
+                    // Later occurrences are even further out of range.
+                    self.repetitions = Some(0);
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Whether `part` is a duration rather than a date and time. A duration may have a
+/// sign, as in `-P1D`, which an RFC 3339 date never starts with.
+fn is_duration(part: &str) -> bool {
+    part.strip_prefix(['-', '+'])
+        .unwrap_or(part)
+        .starts_with('P')
+}
+
+/// Parses the part of an interval starting `offset` bytes into the input as a date
+/// and time.
+fn parse_datetime(part: &str, offset: usize) -> Result<OffsetDateTime, ParseError> {
+    OffsetDateTime::parse(part, &Rfc3339).map_err(|_| ParseError::InvalidDateTime { offset })
+}
+
+/// Parses the part of an interval starting `offset` bytes into the input as a
+/// duration.
+fn parse_duration(part: &str, offset: usize) -> Result<CalendarDuration, ParseError> {
+    part.parse()
+        .map_err(|err: ParseError| err.offset_by(offset))
+}
+
+/// Fails if a date and time of `interval` would not be written as RFC 3339, so that
+/// everything serialized can be deserialized.
+#[cfg(feature = "serde")]
+fn check_rfc3339<E: serde::ser::Error>(interval: &Interval) -> Result<(), E> {
+    let (start, end) = match *interval {
+        Interval::StartEnd(start, end) => (Some(start), Some(end)),
+        Interval::StartDuration(start, _) => (Some(start), None),
+        Interval::DurationEnd(_, end) => (None, Some(end)),
+    };
+    match [start, end]
+        .into_iter()
+        .flatten()
+        .find(|datetime| datetime.format(&Rfc3339).is_err())
+    {
+        Some(datetime) => Err(E::custom(format_args!(
+            "{datetime} cannot be written as an RFC 3339 date and time"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Writes `datetime` as RFC 3339 where possible. Otherwise, the year is expanded to
+/// a sign and at least four digits, and the offset has seconds, so that displaying
+/// never fails.
+fn write_datetime(f: &mut fmt::Formatter<'_>, datetime: &OffsetDateTime) -> fmt::Result {
+    if let Ok(formatted) = datetime.format(&Rfc3339) {
+        return f.write_str(&formatted);
+    }
+
+    let year = datetime.year();
+    if (0..=9999).contains(&year) {
+        write!(f, "{year:04}")?;
+    } else {
+        write!(f, "{year:+05}")?;
+    }
+    write!(
+        f,
+        "-{:02}-{:02}T{:02}:{:02}:{:02}",
+        u8::from(datetime.month()),
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute(),
+        datetime.second()
+    )?;
+    let nanosecond = datetime.nanosecond();
+    if nanosecond != 0 {
+        let digits = format!("{nanosecond:09}");
+        write!(f, ".{}", digits.trim_end_matches('0'))?;
+    }
+
+    let offset = datetime.offset();
+    if offset.is_utc() {
+        return f.write_str("Z");
+    }
+    let (hours, minutes, seconds) = offset.as_hms();
+    let sign = if offset.is_negative() { '-' } else { '+' };
+    write!(
+        f,
+        "{sign}{:02}:{:02}",
+        hours.unsigned_abs(),
+        minutes.unsigned_abs()
+    )?;
+    if seconds != 0 {
+        write!(f, ":{:02}", seconds.unsigned_abs())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn test_forms() {
+        let start = datetime!(2024-03-01 13:00 UTC);
+        let end = datetime!(2024-03-01 15:00 UTC);
+        let two_hours = CalendarDuration::try_from(time::Duration::hours(2)).unwrap();
+
+        for (input, expected) in [
+            (
+                "2024-03-01T13:00:00Z/2024-03-01T15:00:00Z",
+                Interval::StartEnd(start, end),
+            ),
+            (
+                "2024-03-01T13:00:00Z/PT2H",
+                Interval::StartDuration(start, two_hours),
+            ),
+            (
+                "PT2H/2024-03-01T15:00:00Z",
+                Interval::DurationEnd(two_hours, end),
+            ),
+        ] {
+            let interval: Interval = input.parse().unwrap();
+            assert_eq!(interval, expected);
+            assert_eq!(interval.to_string(), input);
+            assert_eq!(interval.resolve(MonthEnd::Clamp), Ok((start, end)));
+        }
+    }
+
+    #[test]
+    fn test_offsets_and_fractions() {
+        let input = "2024-03-01T13:00:00.5+01:00/P1DT0.25S";
+        let interval: Interval = input.parse().unwrap();
+        assert_eq!(interval.to_string(), input);
+        assert_eq!(
+            interval.resolve(MonthEnd::Clamp),
+            Ok((
+                datetime!(2024-03-01 13:00:00.5 +01:00),
+                datetime!(2024-03-02 13:00:00.75 +01:00)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_negative_duration_round_trip() {
+        let start = datetime!(2024-03-01 00:00 UTC);
+        let end = datetime!(2024-02-29 00:00 UTC);
+        let minus_one_day = CalendarDuration::from_days(-1);
+
+        for (interval, expected) in [
+            (
+                Interval::StartEnd(start, end),
+                "2024-03-01T00:00:00Z/2024-02-29T00:00:00Z",
+            ),
+            (
+                Interval::StartDuration(start, minus_one_day),
+                "2024-03-01T00:00:00Z/-P1D",
+            ),
+            (
+                Interval::DurationEnd(minus_one_day, end),
+                "-P1D/2024-02-29T00:00:00Z",
+            ),
+        ] {
+            assert_eq!(interval.to_string(), expected);
+            assert_eq!(expected.parse(), Ok(interval));
+            assert_eq!(interval.resolve(MonthEnd::Clamp), Ok((start, end)));
+        }
+
+        assert_eq!(
+            "+P1D/2024-02-29T00:00:00Z".parse(),
+            Ok(Interval::DurationEnd(CalendarDuration::from_days(1), end))
+        );
+    }
+
+    #[test]
+    fn test_display_outside_rfc3339() {
+        for (interval, expected) in [
+            (
+                Interval::StartEnd(
+                    datetime!(-0001-12-31 23:59:59.25 UTC),
+                    datetime!(0000-01-01 00:00 UTC),
+                ),
+                "-0001-12-31T23:59:59.25Z/0000-01-01T00:00:00Z",
+            ),
+            (
+                Interval::DurationEnd(
+                    CalendarDuration::from_days(1),
+                    datetime!(2024-03-01 00:00 -01:00:30),
+                ),
+                "P1D/2024-03-01T00:00:00-01:00:30",
+            ),
+        ] {
+            assert_eq!(interval.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_resolve_calendar_units() {
+        let interval: Interval = "P1M/2024-03-31T00:00:00Z".parse().unwrap();
+        assert_eq!(
+            interval.resolve(MonthEnd::Clamp),
+            Ok((
+                datetime!(2024-02-29 00:00 UTC),
+                datetime!(2024-03-31 00:00 UTC)
+            ))
+        );
+        assert!(matches!(
+            interval.resolve(MonthEnd::Error),
+            Err(CalendarError::DayOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid() {
+        use ParseError::*;
+
+        for (input, expected) in [
+            ("2024-03-01T13:00:00Z", UnexpectedEnd { offset: 20 }),
+            ("PT2H", UnexpectedEnd { offset: 4 }),
+            ("P1D/P2D", InvalidDateTime { offset: 4 }),
+            ("2024-03-01/PT2H", InvalidDateTime { offset: 0 }),
+            ("2024-03-01T13:00:00Z/PT", MissingComponent { offset: 23 }),
+            (
+                "2024-03-01T13:00:00Z/PT2X",
+                UnexpectedChar {
+                    offset: 24,
+                    found: 'X',
+                },
+            ),
+            (
+                "P1X/2024-03-01T13:00:00Z",
+                UnexpectedChar {
+                    offset: 2,
+                    found: 'X',
+                },
+            ),
+            (
+                "2024-03-01T13:00:00Z/2024-03-01T15:00:00Z/PT1H",
+                InvalidDateTime { offset: 21 },
+            ),
+        ] {
+            assert_eq!(input.parse::<Interval>(), Err(expected), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_repeating_round_trip() {
+        for input in [
+            "R5/2024-01-01T00:00:00Z/PT1H",
+            "R/2024-01-01T00:00:00Z/PT1H",
+            "R0/PT1H/2024-01-01T00:00:00Z",
+            "R2/2024-01-01T00:00:00Z/2024-01-02T00:00:00Z",
+        ] {
+            let repeating: RepeatingInterval = input.parse().unwrap();
+            assert_eq!(repeating.to_string(), input);
+        }
+
+        let repeating: RepeatingInterval = "R5/2024-01-01T00:00:00Z/PT1H".parse().unwrap();
+        assert_eq!(repeating.repetitions(), Some(5));
+        assert_eq!(
+            repeating.interval(),
+            Interval::StartDuration(
+                datetime!(2024-01-01 00:00 UTC),
+                CalendarDuration::try_from(time::Duration::hours(1)).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_occurrences() {
+        let hourly: RepeatingInterval = "R3/2024-01-01T00:00:00Z/PT1H".parse().unwrap();
+        assert_eq!(
+            hourly.occurrences(MonthEnd::Clamp).collect::<Vec<_>>(),
+            [
+                datetime!(2024-01-01 00:00 UTC),
+                datetime!(2024-01-01 01:00 UTC),
+                datetime!(2024-01-01 02:00 UTC),
+            ]
+        );
+
+        let daily: RepeatingInterval = "R/2024-01-01T00:00:00Z/2024-01-02T00:00:00Z"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            daily.occurrences(MonthEnd::Clamp).nth(365),
+            Some(datetime!(2024-12-31 00:00 UTC))
+        );
+
+        let backwards: RepeatingInterval = "R2/P1D/2024-03-01T00:00:00Z".parse().unwrap();
+        assert_eq!(
+            backwards.occurrences(MonthEnd::Clamp).collect::<Vec<_>>(),
+            [
+                datetime!(2024-02-29 00:00 UTC),
+                datetime!(2024-02-28 00:00 UTC),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_occurrences_month_end() {
+        let monthly: RepeatingInterval = "R4/2023-01-31T00:00:00Z/P1M".parse().unwrap();
+        assert_eq!(
+            monthly.occurrences(MonthEnd::Clamp).collect::<Vec<_>>(),
+            [
+                datetime!(2023-01-31 00:00 UTC),
+                datetime!(2023-02-28 00:00 UTC),
+                datetime!(2023-03-31 00:00 UTC),
+                datetime!(2023-04-30 00:00 UTC),
+            ]
+        );
+        assert_eq!(
+            monthly.occurrences(MonthEnd::Overflow).collect::<Vec<_>>(),
+            [
+                datetime!(2023-01-31 00:00 UTC),
+                datetime!(2023-03-03 00:00 UTC),
+                datetime!(2023-03-31 00:00 UTC),
+                datetime!(2023-05-01 00:00 UTC),
+            ]
+        );
+        assert_eq!(
+            monthly.occurrences(MonthEnd::Error).collect::<Vec<_>>(),
+            [
+                datetime!(2023-01-31 00:00 UTC),
+                datetime!(2023-03-31 00:00 UTC),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_occurrences_stop_when_out_of_range() {
+        let yearly: RepeatingInterval = "R/9998-01-01T00:00:00Z/P1Y".parse().unwrap();
+        assert_eq!(yearly.occurrences(MonthEnd::Clamp).count(), 2);
+    }
+
+    #[test]
+    fn test_repeating_invalid() {
+        use ParseError::*;
+
+        for (input, expected) in [
+            (
+                "5/2024-01-01T00:00:00Z/PT1H",
+                MissingDesignator { offset: 0 },
+            ),
+            ("R5", UnexpectedEnd { offset: 2 }),
+            (
+                "R5x/2024-01-01T00:00:00Z/PT1H",
+                UnexpectedChar {
+                    offset: 2,
+                    found: 'x',
+                },
+            ),
+            ("R99999999999/2024-01-01T00:00:00Z/PT1H", Overflow),
+            (
+                "R5/2024-01-01T00:00:00Z/PT",
+                MissingComponent { offset: 26 },
+            ),
+            ("R/2024-01-01T00:00:00Z", UnexpectedEnd { offset: 22 }),
+        ] {
+            assert_eq!(
+                input.parse::<RepeatingInterval>(),
+                Err(expected),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Reservation {
+            slot: Interval,
+        }
+
+        for json in [
+            r#"{"slot":"2024-03-01T13:00:00Z/PT2H"}"#,
+            r#"{"slot":"2024-03-01T13:00:00Z/-PT2H"}"#,
+            r#"{"slot":"-P1M/2024-03-01T13:00:00Z"}"#,
+        ] {
+            let reservation: Reservation = serde_json::from_str(json).unwrap();
+            assert_eq!(serde_json::to_string(&reservation).unwrap(), json);
+        }
+
+        let err = serde_json::from_str::<Reservation>(r#"{"slot":"PT2H"}"#).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with(r#"invalid ISO 8601 interval "PT2H": unexpected end of input"#),
+            "{err}"
+        );
+
+        for slot in [
+            Interval::StartDuration(
+                datetime!(-0001-01-01 00:00 UTC),
+                CalendarDuration::from_days(1),
+            ),
+            Interval::DurationEnd(
+                CalendarDuration::from_days(1),
+                datetime!(2024-03-01 00:00 +01:00:30),
+            ),
+        ] {
+            let err = serde_json::to_string(&Reservation { slot }).unwrap_err();
+            assert!(
+                err.to_string()
+                    .ends_with("cannot be written as an RFC 3339 date and time"),
+                "{err}"
+            );
+            let schedule = RepeatingInterval::new(None, slot);
+            assert!(serde_json::to_string(&schedule).is_err());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_repeating_serde() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Job {
+            schedule: RepeatingInterval,
+        }
+
+        let json = r#"{"schedule":"R/2024-01-01T00:00:00Z/P1W"}"#;
+        let job: Job = serde_json::from_str(json).unwrap();
+        assert_eq!(job.schedule.repetitions(), None);
+        assert_eq!(serde_json::to_string(&job).unwrap(), json);
+
+        let err = serde_json::from_str::<Job>(r#"{"schedule":"R/P1W"}"#).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with(r#"invalid ISO 8601 repeating interval "R/P1W""#),
+            "{err}"
+        );
+    }
+}
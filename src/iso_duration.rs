@@ -0,0 +1,262 @@
+use core::fmt;
+use core::ops::{
+    Add, AddAssign, Deref, DerefMut, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+use core::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::ParseError;
+use crate::parse;
+
+/// A duration that (de)serializes, displays and parses using the ISO 8601 format.
+///
+/// This is a transparent newtype, so the ISO 8601 encoding travels with the value
+/// instead of being attached to a field with `#[serde(with = ...)]`. The encoding is
+/// the same as the matching `serialize` and `deserialize` functions.
+///
+/// The default is [`time::Duration`]. [`std::time::Duration`] is also supported, as
+/// are `chrono::TimeDelta` with the `chrono` feature and `jiff::SignedDuration` and
+/// `jiff::Span` with the `jiff` feature.
+///
+/// ```
+/// use iso8601_duration_serde::IsoDuration;
+/// use time::Duration;
+///
+/// let timeout: IsoDuration = "PT1M30S".parse().unwrap();
+/// assert_eq!(*timeout, Duration::seconds(90));
+/// assert_eq!((timeout * 2).to_string(), "PT3M");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct IsoDuration<T = time::Duration>(pub T);
+
+impl<T> IsoDuration<T> {
+    /// Wraps a duration.
+    #[inline]
+    pub const fn new(duration: T) -> Self {
+        IsoDuration(duration)
+    }
+
+    /// Returns the wrapped duration.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for IsoDuration<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for IsoDuration<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for IsoDuration<T> {
+    fn from(duration: T) -> Self {
+        IsoDuration(duration)
+    }
+}
+
+impl<T: Add<Output = T>> Add for IsoDuration<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        IsoDuration(self.0 + rhs.0)
+    }
+}
+
+impl<T: AddAssign> AddAssign for IsoDuration<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for IsoDuration<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        IsoDuration(self.0 - rhs.0)
+    }
+}
+
+impl<T: SubAssign> SubAssign for IsoDuration<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for IsoDuration<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        IsoDuration(-self.0)
+    }
+}
+
+impl<T: Mul<R, Output = T>, R> Mul<R> for IsoDuration<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: R) -> Self {
+        IsoDuration(self.0 * rhs)
+    }
+}
+
+impl<T: MulAssign<R>, R> MulAssign<R> for IsoDuration<T> {
+    fn mul_assign(&mut self, rhs: R) {
+        self.0 *= rhs;
+    }
+}
+
+impl<T: Div<R, Output = T>, R> Div<R> for IsoDuration<T> {
+    type Output = Self;
+
+    fn div(self, rhs: R) -> Self {
+        IsoDuration(self.0 / rhs)
+    }
+}
+
+impl<T: DivAssign<R>, R> DivAssign<R> for IsoDuration<T> {
+    fn div_assign(&mut self, rhs: R) {
+        self.0 /= rhs;
+    }
+}
+
+/// Implements the ISO 8601 encoding for `IsoDuration<$ty>` using a backend's
+/// `to_components` and `from_components` functions.
+macro_rules! impl_backend {
+    ($ty:ty, $backend:path) => {
+        impl From<IsoDuration<$ty>> for $ty {
+            fn from(duration: IsoDuration<$ty>) -> Self {
+                duration.0
+            }
+        }
+
+        impl fmt::Display for IsoDuration<$ty> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                use $backend as backend;
+                backend::to_components(&self.0).fmt(f)
+            }
+        }
+
+        impl FromStr for IsoDuration<$ty> {
+            type Err = ParseError;
+
+            fn from_str(s: &str) -> Result<Self, ParseError> {
+                use $backend as backend;
+                parse::parse(s)
+                    .and_then(backend::from_components)
+                    .map(IsoDuration)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl Serialize for IsoDuration<$ty> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use $backend as backend;
+                serializer.collect_str(&backend::to_components(&self.0))
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'a> Deserialize<'a> for IsoDuration<$ty> {
+            fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Self, D::Error> {
+                use $backend as backend;
+                crate::deserialize_with(deserializer, |components| {
+                    backend::from_components(components).map(IsoDuration)
+                })
+            }
+        }
+    };
+}
+
+impl_backend!(time::Duration, crate);
+impl_backend!(std::time::Duration, crate::std_time);
+#[cfg(feature = "chrono")]
+impl_backend!(::chrono::TimeDelta, crate::chrono);
+#[cfg(feature = "jiff")]
+impl_backend!(::jiff::SignedDuration, crate::jiff);
+#[cfg(feature = "jiff")]
+impl_backend!(::jiff::Span, crate::jiff::span);
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde")]
+    use std::collections::HashMap;
+
+    use time::Duration;
+
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+    struct TestStruct {
+        duration: IsoDuration,
+        timeout: IsoDuration<std::time::Duration>,
+        by_period: HashMap<IsoDuration, u32>,
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_round_trip() {
+        let test_struct = TestStruct {
+            duration: IsoDuration(Duration::minutes(-30)),
+            timeout: IsoDuration(std::time::Duration::from_millis(1500)),
+            by_period: HashMap::from([(IsoDuration(Duration::days(1)), 7)]),
+        };
+
+        let json = serde_json::to_string(&test_struct).unwrap();
+        assert_eq!(
+            json,
+            r#"{"duration":"-PT30M","timeout":"PT1.5S","by_period":{"P1D":7}}"#
+        );
+
+        let deserialized: TestStruct = serde_json::from_str(&json).unwrap();
+        assert_eq!(test_struct, deserialized);
+    }
+
+    #[test]
+    fn test_display_and_from_str() {
+        let duration: IsoDuration = "P1DT0.25S".parse().unwrap();
+        assert_eq!(
+            duration,
+            IsoDuration(Duration::days(1) + Duration::milliseconds(250))
+        );
+        assert_eq!(duration.to_string(), "P1DT0.25S");
+
+        assert!("P1M".parse::<IsoDuration>().is_err());
+        assert!("-PT1S".parse::<IsoDuration<std::time::Duration>>().is_err());
+    }
+
+    #[test]
+    fn test_ops_and_conversions() {
+        let a = IsoDuration::from(Duration::hours(1));
+        let b = IsoDuration::new(Duration::minutes(30));
+
+        assert_eq!(a + b, IsoDuration(Duration::minutes(90)));
+        assert_eq!(a - b, IsoDuration(Duration::minutes(30)));
+        assert_eq!(-a, IsoDuration(Duration::hours(-1)));
+        assert_eq!(b * 4, a * 2);
+        assert_eq!(a / 2, b);
+        assert!(b < a);
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        c *= 2;
+        c /= 2;
+        assert_eq!(c, b);
+
+        assert_eq!(a.whole_minutes(), 60);
+        let inner: Duration = a.into();
+        assert_eq!(inner, a.into_inner());
+    }
+}
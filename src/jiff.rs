@@ -0,0 +1,325 @@
+//! Serialize and deserialize [`jiff::SignedDuration`] and [`jiff::Span`] using the
+//! ISO 8601 format.
+//!
+//! Requires the `jiff` feature. This module handles [`jiff::SignedDuration`], which
+//! is fixed-length like [`time::Duration`] and produces identical output. The
+//! [`span`] module handles [`jiff::Span`], keeping calendar units intact.
+//!
+//! ```
+//! use jiff::{SignedDuration, Span};
+//! use serde::{Deserialize, Serialize};
+//!
+//! # #[cfg(feature = "serde")]
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "iso8601_duration_serde::jiff")]
+//!     timeout: SignedDuration,
+//!     #[serde(with = "iso8601_duration_serde::jiff::span")]
+//!     billing_period: Span,
+//! }
+//! ```
+
+use core::fmt;
+
+use ::jiff::SignedDuration;
+#[cfg(feature = "serde")]
+use serde::{Deserializer, Serializer};
+use time_core::convert::*;
+
+use crate::components::Components;
+use crate::error::ParseError;
+
+/// Parse a [`jiff::SignedDuration`] from its ISO 8601 representation.
+#[inline]
+pub fn parse(input: &str) -> Result<SignedDuration, ParseError> {
+    crate::parse::parse(input).and_then(from_components)
+}
+
+/// Format a [`jiff::SignedDuration`] using the well-known ISO 8601 format.
+#[inline]
+pub fn format(duration: &SignedDuration) -> String {
+    to_components(duration).to_string()
+}
+
+/// Display a [`jiff::SignedDuration`] using the well-known ISO 8601 format.
+#[inline]
+pub fn display(duration: &SignedDuration) -> impl fmt::Display + use<> {
+    to_components(duration)
+}
+
+/// Serialize a [`jiff::SignedDuration`] using the well-known ISO 8601 format.
+#[cfg(feature = "serde")]
+#[inline]
+pub fn serialize<S: Serializer>(
+    duration: &SignedDuration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&to_components(duration))
+}
+
+/// Deserialize a [`jiff::SignedDuration`] from its ISO 8601 representation.
+#[cfg(feature = "serde")]
+#[inline]
+pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<SignedDuration, D::Error> {
+    crate::deserialize_with(deserializer, from_components)
+}
+
+pub(crate) fn to_components(duration: &SignedDuration) -> Components {
+    Components::fixed(
+        duration.is_negative(),
+        duration.as_secs().unsigned_abs(),
+        duration.subsec_nanos().unsigned_abs(),
+    )
+}
+
+pub(crate) fn from_components(components: Components) -> Result<SignedDuration, ParseError> {
+    let nanoseconds = components.total_nanoseconds()?;
+    let per_second = Nanosecond::per_t::<i128>(Second);
+    let seconds = i64::try_from(nanoseconds / per_second).map_err(|_| ParseError::Overflow)?;
+
+    Ok(SignedDuration::new(
+        seconds,
+        (nanoseconds % per_second) as i32,
+    ))
+}
+
+/// Serialize and deserialize [`jiff::Span`] using the ISO 8601 format.
+///
+/// Every unit is kept as written, so `P1Y2M` round-trips without being normalized
+/// into days. Milliseconds, microseconds and nanoseconds are written as a fraction
+/// of the seconds. Since ISO 8601 only allows weeks on their own, weeks are written
+/// as days when the span has other non-zero units.
+pub mod span {
+    use core::fmt;
+
+    use ::jiff::Span;
+    #[cfg(feature = "serde")]
+    use serde::{Deserializer, Serializer};
+    use time_core::convert::*;
+
+    use crate::components::{Components, Decimal};
+    use crate::error::ParseError;
+
+    /// Parse a [`jiff::Span`] from its ISO 8601 representation.
+    #[inline]
+    pub fn parse(input: &str) -> Result<Span, ParseError> {
+        crate::parse::parse(input).and_then(from_components)
+    }
+
+    /// Format a [`jiff::Span`] using the well-known ISO 8601 format.
+    #[inline]
+    pub fn format(span: &Span) -> String {
+        to_components(span).to_string()
+    }
+
+    /// Display a [`jiff::Span`] using the well-known ISO 8601 format.
+    #[inline]
+    pub fn display(span: &Span) -> impl fmt::Display + use<> {
+        to_components(span)
+    }
+
+    /// Serialize a [`jiff::Span`] using the well-known ISO 8601 format.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn serialize<S: Serializer>(span: &Span, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&to_components(span))
+    }
+
+    /// Deserialize a [`jiff::Span`] from its ISO 8601 representation.
+    ///
+    /// Years, months, weeks and days must be whole numbers. A fraction on the hours,
+    /// minutes or seconds is spread over the smaller units, e.g. `PT1.5H` becomes one
+    /// hour and thirty minutes.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Span, D::Error> {
+        crate::deserialize_with(deserializer, from_components)
+    }
+
+    pub(crate) fn to_components(span: &Span) -> Components {
+        let subsecond = span.get_milliseconds().unsigned_abs() as u128
+            * Nanosecond::per_t::<u128>(Millisecond)
+            + span.get_microseconds().unsigned_abs() as u128
+                * Nanosecond::per_t::<u128>(Microsecond)
+            + span.get_nanoseconds().unsigned_abs() as u128;
+        let per_second = Nanosecond::per_t::<u128>(Second);
+
+        let mut weeks = span.get_weeks().unsigned_abs() as u64;
+        let mut days = span.get_days().unsigned_abs() as u64;
+        let mut components = Components {
+            negative: span.is_negative(),
+            years: Decimal::whole(span.get_years().unsigned_abs() as u64),
+            months: Decimal::whole(span.get_months().unsigned_abs() as u64),
+            hours: Decimal::whole(span.get_hours().unsigned_abs() as u64),
+            minutes: Decimal::whole(span.get_minutes().unsigned_abs()),
+            seconds: Decimal {
+                whole: span.get_seconds().unsigned_abs() + (subsecond / per_second) as u64,
+                nanoseconds: (subsecond % per_second) as u32,
+            },
+            ..Components::default()
+        };
+
+        let only_weeks = components.is_zero() && days == 0;
+        if !only_weeks {
+            days += weeks * Day::per_t::<u64>(Week);
+            weeks = 0;
+        }
+        components.weeks = Decimal::whole(weeks);
+        components.days = Decimal::whole(days);
+
+        components
+    }
+
+    pub(crate) fn from_components(components: Components) -> Result<Span, ParseError> {
+        let calendar = [
+            components.years,
+            components.months,
+            components.weeks,
+            components.days,
+        ];
+        if calendar.iter().any(|value| value.nanoseconds != 0) {
+            return Err(ParseError::FractionalCalendarUnit);
+        }
+
+        // Only the last component can have a fraction, so at most one term is non-zero.
+        let mut fraction = components.hours.nanoseconds as u64 * Second::per_t::<u64>(Hour)
+            + components.minutes.nanoseconds as u64 * Second::per_t::<u64>(Minute)
+            + components.seconds.nanoseconds as u64;
+
+        let extra_minutes = fraction / Nanosecond::per_t::<u64>(Minute);
+        fraction %= Nanosecond::per_t::<u64>(Minute);
+        let extra_seconds = fraction / Nanosecond::per_t::<u64>(Second);
+        fraction %= Nanosecond::per_t::<u64>(Second);
+
+        let too_large = || ParseError::Overflow;
+        let whole = |value: Decimal| i64::try_from(value.whole).map_err(|_| too_large());
+        let years = whole(components.years)?;
+        let months = whole(components.months)?;
+        let weeks = whole(components.weeks)?;
+        let days = whole(components.days)?;
+        let hours = whole(components.hours)?;
+        let minutes = whole(components.minutes)?
+            .checked_add(extra_minutes as i64)
+            .ok_or_else(too_large)?;
+        let seconds = whole(components.seconds)?
+            .checked_add(extra_seconds as i64)
+            .ok_or_else(too_large)?;
+
+        let span = Span::new()
+            .try_years(years)
+            .and_then(|span| span.try_months(months))
+            .and_then(|span| span.try_weeks(weeks))
+            .and_then(|span| span.try_days(days))
+            .and_then(|span| span.try_hours(hours))
+            .and_then(|span| span.try_minutes(minutes))
+            .and_then(|span| span.try_seconds(seconds))
+            .and_then(|span| {
+                span.try_milliseconds((fraction / Nanosecond::per_t::<u64>(Millisecond)) as i64)
+            })
+            .and_then(|span| {
+                span.try_microseconds(
+                    (fraction / Nanosecond::per_t::<u64>(Microsecond) % 1000) as i64,
+                )
+            })
+            .and_then(|span| span.try_nanoseconds((fraction % 1000) as i64))
+            .map_err(|_| too_large())?;
+
+        Ok(if components.negative {
+            span.negate()
+        } else {
+            span
+        })
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use ::jiff::{SignedDuration, Span, ToSpan};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct TestStruct {
+        #[serde(with = "super")]
+        duration: SignedDuration,
+        #[serde(with = "super::span")]
+        span: Span,
+    }
+
+    fn round_trip(duration: SignedDuration, span: Span) -> (String, TestStruct) {
+        let json = serde_json::to_string(&TestStruct { duration, span }).unwrap();
+        (json.clone(), serde_json::from_str(&json).unwrap())
+    }
+
+    #[test]
+    fn test_signed_duration_round_trip() {
+        let cases = [
+            (SignedDuration::ZERO, "PT0S"),
+            (SignedDuration::from_hours(51), "P2DT3H"),
+            (SignedDuration::from_micros(250), "PT0.00025S"),
+            (SignedDuration::from_mins(-30), "-PT30M"),
+            (SignedDuration::MAX, "P106751991167300DT15H30M7.999999999S"),
+            (SignedDuration::MIN, "-P106751991167300DT15H30M8.999999999S"),
+        ];
+
+        for (duration, expected) in cases {
+            let (json, deserialized) = round_trip(duration, Span::new());
+            assert_eq!(
+                json,
+                format!(r#"{{"duration":"{expected}","span":"PT0S"}}"#)
+            );
+            assert_eq!(deserialized.duration, duration);
+        }
+    }
+
+    #[test]
+    fn test_span_round_trip() {
+        let cases = [
+            (Span::new(), "PT0S"),
+            (1.year().months(2), "P1Y2M"),
+            (3.weeks(), "P3W"),
+            (1.month().days(40).hours(36), "P1M40DT36H"),
+            (90.minutes(), "PT90M"),
+            (1.second().milliseconds(250).microseconds(1), "PT1.250001S"),
+            (-(1.year().days(2).minutes(5)), "-P1Y2DT5M"),
+        ];
+
+        for (span, expected) in cases {
+            let (json, deserialized) = round_trip(SignedDuration::ZERO, span);
+            assert_eq!(
+                json,
+                format!(r#"{{"duration":"PT0S","span":"{expected}"}}"#)
+            );
+            assert_eq!(deserialized.span.fieldwise(), span.fieldwise());
+        }
+    }
+
+    #[test]
+    fn test_span_weeks_with_other_units_are_written_as_days() {
+        let (json, deserialized) = round_trip(SignedDuration::ZERO, 1.week().days(2));
+        assert_eq!(json, r#"{"duration":"PT0S","span":"P9D"}"#);
+        assert_eq!(deserialized.span.fieldwise(), 9.days().fieldwise());
+    }
+
+    #[test]
+    fn test_span_fraction_is_spread_over_smaller_units() {
+        let json = r#"{"duration":"PT0S","span":"PT1.5H"}"#;
+        let deserialized: TestStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            deserialized.span.fieldwise(),
+            1.hour().minutes(30).fieldwise()
+        );
+
+        let json = r#"{"duration":"PT0S","span":"PT0.000001S"}"#;
+        let deserialized: TestStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.span.fieldwise(), 1.microsecond().fieldwise());
+
+        let json = r#"{"duration":"PT0S","span":"P1.5M"}"#;
+        assert!(serde_json::from_str::<TestStruct>(json).is_err());
+    }
+
+    #[test]
+    fn test_signed_duration_rejects_calendar_units() {
+        let json = r#"{"duration":"P1Y","span":"PT0S"}"#;
+        assert!(serde_json::from_str::<TestStruct>(json).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! Serialize and deserialize [`time::Duration`], accepting extensions of the ISO 8601
+//! duration syntax.
+//!
+//! This is meant for hand-written or loosely generated inputs. On top of everything
+//! the crate's top-level functions accept, this module accepts:
+//!
+//! - weeks combined with other components, as in ISO 8601-2 (`P1W2D`)
+//! - lowercase designators (`pt1h30m`)
+//! - whitespace before and after the duration
+//! - a fraction on any component, not just the last one (`P1.5DT2H`)
+//! - a minus sign on individual components, as in ISO 8601-2 (`PT1H-15M`), which
+//!   are added up with their signs
+//!
+//! Values are written exactly like the crate's `serialize`. See the
+//! [`strict`](crate::strict) module for the opposite.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use time::Duration;
+//!
+//! # #[cfg(feature = "serde")]
+//! #[derive(Serialize, Deserialize)]
+//! struct Job {
+//!     #[serde(with = "iso8601_duration_serde::lenient")]
+//!     interval: Duration,
+//! }
+//!
+//! let interval = iso8601_duration_serde::lenient::parse(" p1w2d ").unwrap();
+//! assert_eq!(interval, Duration::days(9));
+//! ```
+
+use core::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserializer, Serializer};
+use time::Duration;
+
+use crate::error::ParseError;
+use crate::parse::ParseOptions;
+
+/// Parse a [`time::Duration`] from its ISO 8601 representation, accepting the
+/// extensions listed in the [module documentation](self).
+#[inline]
+pub fn parse(input: &str) -> Result<Duration, ParseError> {
+    crate::parse::parse_with(input, ParseOptions::LENIENT).and_then(crate::from_components)
+}
+
+/// Format a [`time::Duration`] using the well-known ISO 8601 format.
+///
+/// This is the same as [`crate::format`].
+#[inline]
+pub fn format(duration: &Duration) -> String {
+    crate::format(duration)
+}
+
+/// Display a [`time::Duration`] using the well-known ISO 8601 format.
+///
+/// This is the same as [`crate::display`].
+#[inline]
+pub fn display(duration: &Duration) -> impl fmt::Display + use<> {
+    crate::display(duration)
+}
+
+/// Serialize a [`time::Duration`] using the well-known ISO 8601 format.
+///
+/// This is the same as [`crate::serialize`].
+#[cfg(feature = "serde")]
+#[inline]
+pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    crate::serialize(duration, serializer)
+}
+
+/// Deserialize a [`time::Duration`] from its ISO 8601 representation, accepting the
+/// extensions listed in the [module documentation](self).
+#[cfg(feature = "serde")]
+#[inline]
+pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Duration, D::Error> {
+    crate::deserialize_with_options(deserializer, ParseOptions::LENIENT, crate::from_components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_combined_weeks() {
+        assert_eq!(parse("P1W2D"), Ok(Duration::days(9)));
+        assert_eq!(
+            parse("-P1W2DT3H"),
+            Ok(-(Duration::days(9) + Duration::hours(3)))
+        );
+        assert_eq!(parse("P2W"), Ok(Duration::weeks(2)));
+        assert_eq!(parse("P1Y2W"), Err(ParseError::CalendarUnitNotAllowed));
+        assert_eq!(
+            crate::parse("P1W2D"),
+            Err(ParseError::WeeksNotAlone { offset: 3 })
+        );
+    }
+
+    #[test]
+    fn test_parse_relaxed() {
+        assert_eq!(parse(" pt1h30m\t"), Ok(Duration::minutes(90)));
+        assert_eq!(parse("P1.5DT2H"), Ok(Duration::hours(38)));
+        assert_eq!(
+            parse("p1h"),
+            Err(ParseError::MissingTimeDesignator { offset: 2 })
+        );
+        assert_eq!(
+            parse(" PT1H1H"),
+            Err(ParseError::DuplicateComponent { offset: 5 })
+        );
+        for input in ["", " ", "   ", "\t\n"] {
+            assert_eq!(
+                parse(input),
+                Err(ParseError::MissingDesignator { offset: 0 }),
+                "{input:?}"
+            );
+        }
+        assert_eq!(
+            parse("  P "),
+            Err(ParseError::MissingComponent { offset: 3 })
+        );
+    }
+
+    #[test]
+    fn test_parse_component_signs() {
+        assert_eq!(parse("PT1H-15M"), Ok(Duration::minutes(45)));
+        assert_eq!(parse("P1DT-2H"), Ok(Duration::hours(22)));
+        assert_eq!(parse("-P1DT-2H"), Ok(Duration::hours(-22)));
+        assert_eq!(parse("PT-1M30S"), Ok(Duration::seconds(-30)));
+        assert_eq!(parse("P-1Y"), Err(ParseError::CalendarUnitNotAllowed));
+        assert_eq!(
+            crate::parse("PT1H-15M"),
+            Err(ParseError::SignedComponent { offset: 4 })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "super")]
+            duration: Duration,
+        }
+
+        let json = r#"{"duration":"P1W2D"}"#;
+        let deserialized: TestStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.duration, Duration::days(9));
+        assert_eq!(
+            serde_json::to_string(&deserialized).unwrap(),
+            r#"{"duration":"P9D"}"#
+        );
+
+        let err = serde_json::from_str::<TestStruct>(r#"{"duration":"  "}"#).unwrap_err();
+        assert!(err.to_string().contains("at byte 0"), "{err}");
+    }
+}
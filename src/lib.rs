@@ -1,66 +1,221 @@
-use iso8601_duration::Duration as IsoDuration;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+pub mod approximate;
+#[cfg(feature = "serde")]
+pub mod array;
+pub mod lenient;
+#[cfg(feature = "serde")]
+pub mod map;
+#[cfg(feature = "serde")]
+pub mod option;
+#[cfg(feature = "serde")]
+pub mod seq;
+pub mod std_time;
+pub mod strict;
+pub mod style;
+pub mod weeks;
+pub mod xsd;
+
+#[cfg(feature = "chrono")]
+pub mod chrono;
+
+#[cfg(feature = "jiff")]
+pub mod jiff;
+
+#[cfg(feature = "serde_with")]
+mod serde_with;
+
+mod arithmetic;
+mod calendar;
+mod components;
+mod difference;
+mod error;
+mod format;
+mod interval;
+mod iso_duration;
+mod parse;
+
+use core::fmt;
+
+pub use arithmetic::{AddCalendar, MonthEnd};
+pub use calendar::CalendarDuration;
+pub use components::Unit;
+pub use difference::{CalendarDifference, DifferenceOptions, RoundingMode};
+pub use error::{CalendarError, ParseError};
+pub use format::{FormatOptions, Notation, ZeroStyle};
+pub use interval::{Interval, Occurrences, RepeatingInterval};
+pub use iso_duration::IsoDuration;
+#[cfg(feature = "serde_with")]
+pub use crate::serde_with::Iso8601;
+
+use components::Components;
+#[cfg(feature = "serde")]
+use parse::ParseOptions;
+#[cfg(feature = "serde")]
+use serde::de::{self, Visitor};
+#[cfg(feature = "serde")]
+use serde::{Deserializer, Serializer};
 use time::Duration;
 use time_core::convert::*;
 
-/// Serialize an [`time::Duration`] using the well-known ISO 8601 format.
+/// Parse a [`time::Duration`] from its ISO 8601 representation, e.g. `P1DT2H30M`.
+///
+/// This accepts exactly what `deserialize` accepts.
+///
+/// ```
+/// use time::Duration;
+///
+/// let duration = iso8601_duration_serde::parse("PT1M30.5S").unwrap();
+/// assert_eq!(duration, Duration::seconds(90) + Duration::milliseconds(500));
+/// ```
 #[inline]
-pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
-    let mut seconds = duration.whole_seconds();
-    let nanoseconds = duration.subsec_nanoseconds();
-
-    let days = seconds / Second::per_t::<i64>(Day);
-    seconds = seconds % Second::per_t::<i64>(Day);
-
-    let hours = seconds / Second::per_t::<i64>(Hour);
-    seconds = seconds % Second::per_t::<i64>(Hour);
-
-    let minutes = seconds / Second::per_t::<i64>(Minute);
-    seconds = seconds % Second::per_t::<i64>(Minute);
+pub fn parse(input: &str) -> Result<Duration, ParseError> {
+    parse::parse(input).and_then(from_components)
+}
 
-    let seconds_f32 =
-        seconds as f32 + (nanoseconds as f64 / Nanosecond::per_t::<f64>(Second)) as f32;
+/// Format a [`time::Duration`] using the well-known ISO 8601 format.
+///
+/// This produces exactly what `serialize` writes.
+///
+/// ```
+/// use time::Duration;
+///
+/// assert_eq!(iso8601_duration_serde::format(&Duration::hours(-36)), "-P1DT12H");
+/// ```
+#[inline]
+pub fn format(duration: &Duration) -> String {
+    to_components(duration).to_string()
+}
 
-    let iso_duration = IsoDuration::new(
-        0f32,
-        0f32,
-        days as f32,
-        hours as f32,
-        minutes as f32,
-        seconds_f32,
-    );
+/// Display a [`time::Duration`] using the well-known ISO 8601 format.
+///
+/// The returned value can be written into any [`fmt::Write`] without allocating an
+/// intermediate string.
+///
+/// ```
+/// use std::fmt::Write;
+///
+/// use time::Duration;
+///
+/// let mut out = String::from("timeout=");
+/// write!(out, "{}", iso8601_duration_serde::display(&Duration::minutes(5))).unwrap();
+/// assert_eq!(out, "timeout=PT5M");
+/// ```
+#[inline]
+pub fn display(duration: &Duration) -> impl fmt::Display + use<> {
+    to_components(duration)
+}
 
-    iso_duration.serialize(serializer)
+/// Serialize an [`time::Duration`] using the well-known ISO 8601 format.
+///
+/// The value is written exactly, with up to nine fractional digits on the seconds.
+/// Negative durations are written with a leading minus sign, e.g. `-PT30M`.
+#[cfg(feature = "serde")]
+#[inline]
+pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&to_components(duration))
 }
 
 /// Deserialize an [`time::Duration`] from its ISO 8601 representation.
+///
+/// Each component is read as an exact decimal, so no precision is lost. A leading `-`
+/// or `+` sign is accepted. Inputs whose value does not fit in a [`time::Duration`]
+/// are rejected.
+#[cfg(feature = "serde")]
 #[inline]
 pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Duration, D::Error> {
-    let duration = IsoDuration::deserialize(deserializer)?;
+    deserialize_with(deserializer, from_components)
+}
+
+/// Deserializes an ISO 8601 string and builds the target type from its parsed
+/// components, which is shared by every duration backend.
+#[cfg(feature = "serde")]
+fn deserialize_with<'a, D: Deserializer<'a>, T>(
+    deserializer: D,
+    from_components: fn(Components) -> Result<T, ParseError>,
+) -> Result<T, D::Error> {
+    deserialize_with_options(deserializer, ParseOptions::DEFAULT, from_components)
+}
 
-    if duration.year > 0.0 || duration.month > 0.0 {
-        return Err(serde::de::Error::custom(
-            "Duration::year and Duration::month must be zero",
-        ));
+/// Like [`deserialize_with`], but parses with the given syntax options.
+#[cfg(feature = "serde")]
+fn deserialize_with_options<'a, D: Deserializer<'a>, T>(
+    deserializer: D,
+    options: ParseOptions,
+    from_components: fn(Components) -> Result<T, ParseError>,
+) -> Result<T, D::Error> {
+    deserializer.deserialize_str(DurationVisitor {
+        options,
+        from_components,
+    })
+}
+
+#[cfg(feature = "serde")]
+struct DurationVisitor<T> {
+    options: ParseOptions,
+    from_components: fn(Components) -> Result<T, ParseError>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> Visitor<'_> for DurationVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an ISO 8601 duration")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        parse::parse_with(v, self.options)
+            .and_then(self.from_components)
+            .map_err(|err| E::custom(format_args!("invalid ISO 8601 duration {v:?}: {err}")))
     }
+}
 
-    let seconds_fract = duration.day.fract() * Second::per_t::<f32>(Day)
-        + duration.hour.fract() * Second::per_t::<f32>(Hour)
-        + duration.minute.fract() * Second::per_t::<f32>(Minute)
-        + duration.second.fract();
+fn to_components(duration: &Duration) -> Components {
+    Components::fixed(
+        duration.is_negative(),
+        duration.whole_seconds().unsigned_abs(),
+        duration.subsec_nanoseconds().unsigned_abs(),
+    )
+}
 
-    let seconds = duration.day as i64 * Second::per_t::<i64>(Day)
-        + duration.hour as i64 * Second::per_t::<i64>(Hour)
-        + duration.minute as i64 * Second::per_t::<i64>(Minute)
-        + duration.second as i64
-        + seconds_fract as i64;
+fn from_components(components: Components) -> Result<Duration, ParseError> {
+    from_nanoseconds(components.total_nanoseconds()?)
+}
 
-    let nanoseconds = (seconds_fract.fract() * Nanosecond::per_t::<f32>(Second)) as i32;
+fn from_nanoseconds(nanoseconds: i128) -> Result<Duration, ParseError> {
+    let per_second = Nanosecond::per_t::<i128>(Second);
+    let seconds = i64::try_from(nanoseconds / per_second).map_err(|_| ParseError::Overflow)?;
 
-    Ok(Duration::new(seconds, nanoseconds))
+    Ok(Duration::new(seconds, (nanoseconds % per_second) as i32))
 }
 
 #[cfg(test)]
+mod api_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_match_serde() {
+        let duration = Duration::days(2) + Duration::hours(3) + Duration::nanoseconds(250);
+        assert_eq!(format(&duration), "P2DT3H0.00000025S");
+        assert_eq!(display(&duration).to_string(), format(&duration));
+        assert_eq!(parse(&format(&duration)), Ok(duration));
+
+        assert_eq!(parse("-PT30M"), Ok(Duration::minutes(-30)));
+        assert_eq!(format(&Duration::ZERO), "PT0S");
+    }
+
+    #[test]
+    fn test_parse_invalid_is_error() {
+        assert_eq!(parse(""), Err(ParseError::MissingDesignator { offset: 0 }));
+        assert_eq!(parse("P"), Err(ParseError::MissingComponent { offset: 1 }));
+        assert_eq!(parse("P1Y"), Err(ParseError::CalendarUnitNotAllowed));
+        assert_eq!(parse("-P1M"), Err(ParseError::CalendarUnitNotAllowed));
+        assert_eq!(parse("P-1M"), Err(ParseError::SignedComponent { offset: 1 }));
+        assert_eq!(parse("PT1.5"), Err(ParseError::MissingDesignator { offset: 5 }));
+        assert_eq!(parse("30 minutes"), Err(ParseError::MissingDesignator { offset: 0 }));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
 mod tests {
     use super::*;
     use serde::{Serialize, Deserialize};
@@ -143,14 +298,10 @@ mod tests {
         };
 
         let json = serde_json::to_string(&test_struct).unwrap();
-        // Depending on serialization implementation, fractional seconds might be formatted differently
-        
+        assert_eq!(json, r#"{"duration":"PT10.5S"}"#);
+
         let deserialized: TestStruct = serde_json::from_str(&json).unwrap();
-        // Allow small differences in precision due to floating point arithmetic
-        assert_eq!(deserialized.duration.whole_seconds(), test_struct.duration.whole_seconds());
-        // Check that the difference is less than 1 second
-        assert!(deserialized.duration - test_struct.duration < Duration::seconds(1));
-        assert!(test_struct.duration - deserialized.duration < Duration::seconds(1));
+        assert_eq!(test_struct, deserialized);
     }
 
     #[test]
@@ -185,8 +336,113 @@ mod tests {
         // Fractional seconds
         let json = r#"{"duration":"PT10.5S"}"#;
         let deserialized: TestStruct = serde_json::from_str(json).unwrap();
-        // Check that deserialization works (exact value might vary due to float precision)
-        assert!(deserialized.duration > Duration::seconds(10));
-        assert!(deserialized.duration < Duration::seconds(11));
+        assert_eq!(deserialized.duration, Duration::seconds(10) + Duration::milliseconds(500));
+
+        // Weeks
+        let json = r#"{"duration":"P2W"}"#;
+        let deserialized: TestStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.duration, Duration::weeks(2));
+
+        // Fraction on the last component
+        let json = r#"{"duration":"PT1.5H"}"#;
+        let deserialized: TestStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.duration, Duration::minutes(90));
+    }
+
+    #[test]
+    fn test_serialize_exact_precision() {
+        let cases = [
+            (Duration::ZERO, "PT0S"),
+            (Duration::microseconds(250), "PT0.00025S"),
+            (Duration::nanoseconds(1), "PT0.000000001S"),
+            (Duration::days(1) + Duration::nanoseconds(999_999_999), "P1DT0.999999999S"),
+            (Duration::days(36500) + Duration::seconds(1), "P36500DT1S"),
+            (Duration::seconds(16_777_217), "P194DT4H20M17S"),
+            (Duration::MAX, "P106751991167300DT15H30M7.999999999S"),
+        ];
+
+        for (duration, expected) in cases {
+            let json = serde_json::to_string(&TestStruct { duration }).unwrap();
+            assert_eq!(json, format!(r#"{{"duration":"{expected}"}}"#));
+        }
+    }
+
+    #[test]
+    fn test_negative_duration() {
+        let cases = [
+            (Duration::minutes(-30), "-PT30M"),
+            (Duration::days(-1), "-P1D"),
+            (-(Duration::days(2) + Duration::hours(3)), "-P2DT3H"),
+            (Duration::milliseconds(-1500), "-PT1.5S"),
+            (Duration::MIN, "-P106751991167300DT15H30M8.999999999S"),
+        ];
+
+        for (duration, expected) in cases {
+            let test_struct = TestStruct { duration };
+            let json = serde_json::to_string(&test_struct).unwrap();
+            assert_eq!(json, format!(r#"{{"duration":"{expected}"}}"#));
+
+            let deserialized: TestStruct = serde_json::from_str(&json).unwrap();
+            assert_eq!(test_struct, deserialized);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_explicit_plus_sign() {
+        let json = r#"{"duration":"+PT30M"}"#;
+        let deserialized: TestStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.duration, Duration::minutes(30));
+    }
+
+    #[test]
+    fn test_round_trip_is_exact() {
+        let cases = [
+            Duration::ZERO,
+            Duration::nanoseconds(1),
+            Duration::microseconds(250),
+            Duration::seconds(16_777_217) + Duration::nanoseconds(123_456_789),
+            Duration::days(36500) + Duration::seconds(1),
+            Duration::MAX,
+            Duration::MIN,
+            -Duration::nanoseconds(1),
+        ];
+
+        for duration in cases {
+            let json = serde_json::to_string(&TestStruct { duration }).unwrap();
+            let deserialized: TestStruct = serde_json::from_str(&json).unwrap();
+            assert_eq!(deserialized.duration, duration, "{json}");
+        }
+    }
+
+    #[test]
+    fn test_deserialize_overflow_is_error() {
+        let json = r#"{"duration":"P106751991167301D"}"#;
+        assert!(serde_json::from_str::<TestStruct>(json).is_err());
+
+        let json = r#"{"duration":"-P106751991167300DT15H30M9S"}"#;
+        assert!(serde_json::from_str::<TestStruct>(json).is_err());
+
+        let json = r#"{"duration":"PT18446744073709551616S"}"#;
+        assert!(serde_json::from_str::<TestStruct>(json).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_error_message() {
+        let json = r#"{"duration":"PT1D"}"#;
+        let err = serde_json::from_str::<TestStruct>(json).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with(r#"invalid ISO 8601 duration "PT1D": unexpected character 'D' at byte 3"#),
+            "{err}"
+        );
+
+        let json = r#"{"duration":"P1M"}"#;
+        let err = serde_json::from_str::<TestStruct>(json).unwrap_err();
+        assert!(
+            err.to_string().starts_with(
+                r#"invalid ISO 8601 duration "P1M": years and months are not allowed in a fixed-length duration"#
+            ),
+            "{err}"
+        );
     }
 }
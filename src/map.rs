@@ -0,0 +1,113 @@
+//! Serialize and deserialize maps with [`time::Duration`] values using the ISO 8601
+//! format.
+//!
+//! Works with any map that can be iterated by reference and collected, such as
+//! [`HashMap`](std::collections::HashMap) or [`BTreeMap`](std::collections::BTreeMap).
+//! Keys are (de)serialized with their own implementations.
+//!
+//! ```
+//! use std::collections::HashMap;
+//!
+//! use serde::{Deserialize, Serialize};
+//! use time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Timeouts {
+//!     #[serde(with = "iso8601_duration_serde::map")]
+//!     per_tenant: HashMap<String, Duration>,
+//! }
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use time::Duration;
+
+use crate::IsoDuration;
+
+/// Serialize a map with [`time::Duration`] values as a map of ISO 8601 strings.
+#[inline]
+pub fn serialize<S, K, M>(map: &M, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize,
+    for<'m> &'m M: IntoIterator<Item = (&'m K, &'m Duration)>,
+{
+    serializer.collect_map(
+        map.into_iter()
+            .map(|(key, value)| (key, IsoDuration(*value))),
+    )
+}
+
+/// Deserialize a map with [`time::Duration`] values from a map of ISO 8601 strings.
+#[inline]
+pub fn deserialize<'a, D, K, M>(deserializer: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'a>,
+    K: Deserialize<'a>,
+    M: FromIterator<(K, Duration)>,
+{
+    deserializer.deserialize_map(MapVisitor(PhantomData))
+}
+
+struct MapVisitor<K, M>(PhantomData<(K, M)>);
+
+impl<'a, K: Deserialize<'a>, M: FromIterator<(K, Duration)>> Visitor<'a> for MapVisitor<K, M> {
+    type Value = M;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map with ISO 8601 duration values")
+    }
+
+    fn visit_map<A: MapAccess<'a>>(self, mut map: A) -> Result<M, A::Error> {
+        core::iter::from_fn(|| map.next_entry::<K, IsoDuration>().transpose())
+            .map(|entry| entry.map(|(key, IsoDuration(value))| (key, value)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+    struct TestStruct {
+        #[serde(with = "super")]
+        tenants: BTreeMap<String, Duration>,
+        #[serde(with = "super")]
+        shards: HashMap<u32, Duration>,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let test_struct = TestStruct {
+            tenants: BTreeMap::from([
+                ("acme".to_owned(), Duration::seconds(30)),
+                (
+                    "globex".to_owned(),
+                    Duration::minutes(2) + Duration::milliseconds(250),
+                ),
+            ]),
+            shards: HashMap::from([(7, Duration::hours(-1))]),
+        };
+
+        let json = serde_json::to_string(&test_struct).unwrap();
+        assert_eq!(
+            json,
+            r#"{"tenants":{"acme":"PT30S","globex":"PT2M0.25S"},"shards":{"7":"-PT1H"}}"#
+        );
+
+        let deserialized: TestStruct = serde_json::from_str(&json).unwrap();
+        assert_eq!(test_struct, deserialized);
+    }
+
+    #[test]
+    fn test_deserialize_invalid_value_is_error() {
+        let json = r#"{"tenants":{"acme":"soon"},"shards":{}}"#;
+        assert!(serde_json::from_str::<TestStruct>(json).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! Serialize and deserialize [`Option<time::Duration>`] using the ISO 8601 format.
+//!
+//! `None` is written as `null` and `Some` as the same string the crate's top-level
+//! functions produce. Combine with `#[serde(default)]` to accept missing fields.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(
+//!         default,
+//!         with = "iso8601_duration_serde::option",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     timeout: Option<Duration>,
+//! }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serializer};
+use time::Duration;
+
+use crate::IsoDuration;
+
+/// Serialize an [`Option<time::Duration>`] as `null` or an ISO 8601 string.
+#[inline]
+pub fn serialize<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serializer.serialize_some(&IsoDuration(*duration)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Deserialize an [`Option<time::Duration>`] from `null` or an ISO 8601 string.
+#[inline]
+pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    Ok(Option::<IsoDuration>::deserialize(deserializer)?.map(|IsoDuration(duration)| duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+    struct TestStruct {
+        #[serde(with = "super")]
+        duration: Option<Duration>,
+    }
+
+    #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+    struct SkippedStruct {
+        #[serde(default, with = "super", skip_serializing_if = "Option::is_none")]
+        duration: Option<Duration>,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cases = [
+            (Some(Duration::minutes(30)), r#"{"duration":"PT30M"}"#),
+            (
+                Some(Duration::milliseconds(-1500)),
+                r#"{"duration":"-PT1.5S"}"#,
+            ),
+            (None, r#"{"duration":null}"#),
+        ];
+
+        for (duration, expected) in cases {
+            let test_struct = TestStruct { duration };
+            let json = serde_json::to_string(&test_struct).unwrap();
+            assert_eq!(json, expected);
+
+            let deserialized: TestStruct = serde_json::from_str(&json).unwrap();
+            assert_eq!(test_struct, deserialized);
+        }
+    }
+
+    #[test]
+    fn test_default_and_skip_serializing_if() {
+        let skipped = SkippedStruct { duration: None };
+        assert_eq!(serde_json::to_string(&skipped).unwrap(), "{}");
+        assert_eq!(
+            serde_json::from_str::<SkippedStruct>("{}").unwrap(),
+            skipped
+        );
+
+        let present = SkippedStruct {
+            duration: Some(Duration::days(1)),
+        };
+        assert_eq!(
+            serde_json::to_string(&present).unwrap(),
+            r#"{"duration":"P1D"}"#
+        );
+    }
+
+    #[test]
+    fn test_deserialize_invalid_is_error() {
+        assert!(serde_json::from_str::<TestStruct>(r#"{"duration":"P1Y"}"#).is_err());
+        assert!(serde_json::from_str::<TestStruct>(r#"{"duration":5}"#).is_err());
+    }
+}
@@ -0,0 +1,766 @@
+use crate::components::{Components, Decimal, Unit};
+use crate::error::ParseError;
+
+/// Which extensions of the ISO 8601 duration syntax the parser accepts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ParseOptions {
+    /// Accept a leading `-` or `+` sign on the whole duration.
+    pub(crate) sign: bool,
+    /// Accept weeks together with other components, as in ISO 8601-2 (`P1W2D`).
+    pub(crate) combined_weeks: bool,
+    /// Accept lowercase designators, e.g. `pt1h`.
+    pub(crate) lowercase: bool,
+    /// Accept whitespace before and after the duration.
+    pub(crate) whitespace: bool,
+    /// Accept a fraction on any component, not just the last one.
+    pub(crate) fractions_anywhere: bool,
+    /// Accept a minus sign on individual components, as in ISO 8601-2 (`PT1H-15M`).
+    pub(crate) component_signs: bool,
+    /// Follow the XML Schema lexical rules instead of ISO 8601: no `+` sign, no comma,
+    /// no alternative format, and a fraction only on the seconds.
+    pub(crate) xsd: bool,
+    /// The units that may appear.
+    pub(crate) units: &'static [Unit],
+}
+
+impl ParseOptions {
+    /// ISO 8601-1 without extensions, as accepted by the [`strict`](crate::strict)
+    /// module.
+    pub(crate) const STRICT: Self = ParseOptions {
+        sign: false,
+        combined_weeks: false,
+        lowercase: false,
+        whitespace: false,
+        fractions_anywhere: false,
+        component_signs: false,
+        xsd: false,
+        units: &Unit::ALL,
+    };
+
+    /// The syntax accepted by the crate's top-level functions: ISO 8601-1 with a
+    /// leading sign.
+    pub(crate) const DEFAULT: Self = ParseOptions {
+        sign: true,
+        ..ParseOptions::STRICT
+    };
+
+    /// The syntax accepted by the [`lenient`](crate::lenient) module.
+    pub(crate) const LENIENT: Self = ParseOptions {
+        sign: true,
+        combined_weeks: true,
+        lowercase: true,
+        whitespace: true,
+        fractions_anywhere: true,
+        component_signs: true,
+        ..ParseOptions::STRICT
+    };
+}
+
+/// Parses an ISO 8601 duration such as `P1DT2H30M0.5S` into its components.
+///
+/// Every value is read as an exact decimal, with either `.` or `,` as the decimal
+/// sign. Only the last component may have a fraction, and the week designator may
+/// only be used on its own (`P2W`). The whole duration may be preceded by a `-` or
+/// `+` sign.
+///
+/// The alternative format is also accepted, both extended (`P0001-02-03T04:05:06`)
+/// and basic (`P00010203T040506`). Its components must stay below their carry-over
+/// points: 12 months, 30 days, 24 hours, 60 minutes and 60 seconds. Only the seconds
+/// may have a fraction.
+pub(crate) fn parse(input: &str) -> Result<Components, ParseError> {
+    parse_with(input, ParseOptions::DEFAULT)
+}
+
+/// The characters XSD treats as whitespace: #x20, #x9, #xA and #xD.
+const XSD_WHITESPACE: [char; 4] = [' ', '\t', '\n', '\r'];
+
+/// Parses an ISO 8601 duration, accepting the extensions enabled in `options`.
+pub(crate) fn parse_with(input: &str, options: ParseOptions) -> Result<Components, ParseError> {
+    let mut cursor = Cursor {
+        input,
+        offset: 0,
+        options,
+    };
+    if options.whitespace {
+        // XSD only collapses spaces, tabs and line breaks, not all Unicode whitespace.
+        let (trimmed, start) = if options.xsd {
+            let trimmed = input.trim_end_matches(XSD_WHITESPACE);
+            (trimmed, trimmed.trim_start_matches(XSD_WHITESPACE))
+        } else {
+            let trimmed = input.trim_end();
+            (trimmed, trimmed.trim_start())
+        };
+        cursor.input = trimmed;
+        cursor.offset = trimmed.len() - start.len();
+    }
+
+    let negative = match cursor.peek() {
+        Some('-' | '+') if !options.sign => return Err(cursor.unexpected()),
+        Some('+') if options.xsd => return Err(cursor.unexpected()),
+        Some('-') => true,
+        _ => false,
+    };
+    if matches!(cursor.peek(), Some('-' | '+')) {
+        cursor.bump();
+    }
+
+    if !cursor.eat_designator('P') {
+        return Err(ParseError::MissingDesignator {
+            offset: cursor.offset,
+        });
+    }
+
+    let mut components = Components {
+        negative,
+        ..Components::default()
+    };
+    if !options.xsd && is_alternative(cursor.rest()) {
+        cursor.alternative(&mut components)?;
+        return Ok(components);
+    }
+
+    let mut previous: Option<Unit> = None;
+    let mut in_time = false;
+    let mut fraction_at = None;
+
+    loop {
+        if cursor.eat_designator('T') {
+            if in_time {
+                return Err(cursor.unexpected_previous());
+            }
+            in_time = true;
+            if cursor.is_at_end() {
+                return Err(ParseError::MissingComponent {
+                    offset: cursor.offset,
+                });
+            }
+        }
+
+        if cursor.is_at_end() {
+            break;
+        }
+
+        if let Some(offset) = fraction_at {
+            return Err(ParseError::FractionNotOnLastComponent { offset });
+        }
+
+        let start = cursor.offset;
+        let negated = cursor.eat('-');
+        if negated && !options.component_signs {
+            return Err(ParseError::SignedComponent { offset: start });
+        }
+        let value = cursor.decimal()?;
+        if value.fractional && !options.fractions_anywhere {
+            fraction_at = Some(start);
+        }
+
+        let unit = match (in_time, cursor.peek_designator()) {
+            (false, Some('Y')) => Unit::Year,
+            (false, Some('M')) => Unit::Month,
+            (false, Some('W')) => Unit::Week,
+            (false, Some('D')) => Unit::Day,
+            (true, Some('H')) => Unit::Hour,
+            (true, Some('M')) => Unit::Minute,
+            (true, Some('S')) => Unit::Second,
+            (false, Some('H' | 'S')) => {
+                return Err(ParseError::MissingTimeDesignator {
+                    offset: cursor.offset,
+                });
+            }
+            (_, None) => {
+                return Err(ParseError::MissingDesignator {
+                    offset: cursor.offset,
+                });
+            }
+            _ => return Err(cursor.unexpected()),
+        };
+        if !options.units.contains(&unit) {
+            return Err(cursor.unexpected());
+        }
+        if options.xsd && value.fractional && unit != Unit::Second {
+            return Err(ParseError::FractionNotOnSeconds { offset: start });
+        }
+
+        if previous == Some(unit) {
+            return Err(ParseError::DuplicateComponent { offset: start });
+        }
+        if previous.is_some_and(|previous| previous > unit) {
+            return Err(ParseError::ComponentOutOfOrder { offset: start });
+        }
+        let combined = (unit == Unit::Week && previous.is_some()) || previous == Some(Unit::Week);
+        if combined && !options.combined_weeks {
+            return Err(ParseError::WeeksNotAlone { offset: start });
+        }
+
+        cursor.bump();
+        *components.get_mut(unit) = value.decimal;
+        components.negated[unit as usize] = negated;
+        previous = Some(unit);
+    }
+
+    if previous.is_none() {
+        return Err(ParseError::MissingComponent {
+            offset: cursor.offset,
+        });
+    }
+
+    Ok(components)
+}
+
+/// Whether `rest`, the input after `P`, is in the alternative format: four digits
+/// and a hyphen (extended), or eight digits and `T` (basic).
+fn is_alternative(rest: &str) -> bool {
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    match rest.as_bytes().get(digits) {
+        Some(b'-') => digits == 4,
+        Some(b'T' | b't') => digits == 8,
+        _ => false,
+    }
+}
+
+/// A number read from the input, remembering whether it was written with a fraction.
+struct Number {
+    decimal: Decimal,
+    fractional: bool,
+}
+
+/// A position in the input, tracked as a byte offset for error reporting.
+struct Cursor<'a> {
+    input: &'a str,
+    offset: usize,
+    options: ParseOptions,
+}
+
+impl Cursor<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.offset..]
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.offset == self.input.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) {
+        self.offset += self.peek().map_or(0, char::len_utf8);
+    }
+
+    /// The character at the cursor, uppercased if lowercase designators are accepted.
+    fn peek_designator(&self) -> Option<char> {
+        let found = self.peek()?;
+        Some(if self.options.lowercase {
+            found.to_ascii_uppercase()
+        } else {
+            found
+        })
+    }
+
+    fn eat_designator(&mut self, expected: char) -> bool {
+        let found = self.peek_designator() == Some(expected);
+        if found {
+            self.bump();
+        }
+        found
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let found = self.peek() == Some(expected);
+        if found {
+            self.bump();
+        }
+        found
+    }
+
+    /// The error for the character at the cursor, or for the end of the input.
+    fn unexpected(&self) -> ParseError {
+        match self.peek() {
+            Some(found) => ParseError::UnexpectedChar {
+                offset: self.offset,
+                found,
+            },
+            None => ParseError::UnexpectedEnd {
+                offset: self.offset,
+            },
+        }
+    }
+
+    /// The error for the character just before the cursor.
+    fn unexpected_previous(&self) -> ParseError {
+        let found = self.input[..self.offset]
+            .chars()
+            .next_back()
+            .unwrap_or_default();
+        ParseError::UnexpectedChar {
+            offset: self.offset - found.len_utf8(),
+            found,
+        }
+    }
+
+    /// Reads the leading ASCII digits.
+    fn digits(&mut self) -> &str {
+        let start = self.offset;
+        let len = self
+            .rest()
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest().len());
+        self.offset += len;
+        &self.input[start..self.offset]
+    }
+
+    /// Reads a decimal number such as `12`, `0.25` or `0,25`.
+    fn decimal(&mut self) -> Result<Number, ParseError> {
+        let whole = self.digits();
+        if whole.is_empty() {
+            return Err(self.unexpected());
+        }
+
+        let whole = whole
+            .bytes()
+            .try_fold(0u64, |acc, digit| {
+                acc.checked_mul(10)?.checked_add(u64::from(digit - b'0'))
+            })
+            .ok_or(ParseError::Overflow)?;
+
+        Ok(match self.fraction()? {
+            Some(nanoseconds) => Number {
+                decimal: Decimal { whole, nanoseconds },
+                fractional: true,
+            },
+            None => Number {
+                decimal: Decimal::whole(whole),
+                fractional: false,
+            },
+        })
+    }
+
+    /// Reads a decimal sign and the digits after it as nanoseconds, if there is one.
+    fn fraction(&mut self) -> Result<Option<u32>, ParseError> {
+        let comma = |cursor: &mut Self| !cursor.options.xsd && cursor.eat(',');
+        if !(self.eat('.') || comma(self)) {
+            return Ok(None);
+        }
+
+        let start = self.offset;
+        let xsd = self.options.xsd;
+        let fraction = self.digits();
+        if fraction.is_empty() {
+            return Err(self.unexpected());
+        }
+        // XSD allows any number of digits, so the ones past nanoseconds are dropped.
+        if fraction.len() > 9 && !xsd {
+            return Err(ParseError::TooManyFractionDigits { offset: start });
+        }
+
+        let nanoseconds = fraction
+            .bytes()
+            .chain(core::iter::repeat(b'0'))
+            .take(9)
+            .fold(0u32, |acc, digit| acc * 10 + u32::from(digit - b'0'));
+        Ok(Some(nanoseconds))
+    }
+
+    /// Reads the rest of a duration in the alternative format, after `P`.
+    fn alternative(&mut self, components: &mut Components) -> Result<(), ParseError> {
+        let extended = self.rest().as_bytes().get(4) == Some(&b'-');
+        let separator = |cursor: &mut Self, separator| {
+            if extended && !cursor.eat(separator) {
+                return Err(cursor.unexpected());
+            }
+            Ok(())
+        };
+
+        components.years = Decimal::whole(self.fixed_digits(4, 10_000)?);
+        separator(self, '-')?;
+        components.months = Decimal::whole(self.fixed_digits(2, 12)?);
+        separator(self, '-')?;
+        components.days = Decimal::whole(self.fixed_digits(2, 30)?);
+        if !self.eat_designator('T') {
+            return Err(self.unexpected());
+        }
+        components.hours = Decimal::whole(self.fixed_digits(2, 24)?);
+        separator(self, ':')?;
+        components.minutes = Decimal::whole(self.fixed_digits(2, 60)?);
+        separator(self, ':')?;
+        components.seconds = Decimal::whole(self.fixed_digits(2, 60)?);
+        components.seconds.nanoseconds = self.fraction()?.unwrap_or(0);
+
+        if !self.is_at_end() {
+            return Err(self.unexpected());
+        }
+        Ok(())
+    }
+
+    /// Reads exactly `len` digits as a number below `limit`.
+    fn fixed_digits(&mut self, len: usize, limit: u64) -> Result<u64, ParseError> {
+        let start = self.offset;
+        for _ in 0..len {
+            if !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                return Err(self.unexpected());
+            }
+            self.bump();
+        }
+
+        let value = self.input[start..self.offset]
+            .bytes()
+            .fold(0, |acc, digit| acc * 10 + u64::from(digit - b'0'));
+        if value >= limit {
+            return Err(ParseError::ComponentOutOfRange { offset: start });
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(whole: u64, nanoseconds: u32) -> Decimal {
+        Decimal { whole, nanoseconds }
+    }
+
+    #[test]
+    fn test_parse_components() {
+        let components = parse("P1Y2M3DT4H5M6.000000007S").unwrap();
+        assert_eq!(components.years, decimal(1, 0));
+        assert_eq!(components.months, decimal(2, 0));
+        assert_eq!(components.days, decimal(3, 0));
+        assert_eq!(components.hours, decimal(4, 0));
+        assert_eq!(components.minutes, decimal(5, 0));
+        assert_eq!(components.seconds, decimal(6, 7));
+
+        assert_eq!(parse("PT0.25S").unwrap().seconds, decimal(0, 250_000_000));
+        assert_eq!(parse("PT0,25S").unwrap().seconds, decimal(0, 250_000_000));
+        assert_eq!(parse("P1,5D").unwrap().days, decimal(1, 500_000_000));
+        assert_eq!(parse("P2W").unwrap().weeks, decimal(2, 0));
+    }
+
+    #[test]
+    fn test_parse_sign() {
+        assert!(!parse("P1D").unwrap().negative);
+        assert!(!parse("+P1D").unwrap().negative);
+        assert!(parse("-P1D").unwrap().negative);
+
+        assert_eq!(
+            parse("-PT1.5S").unwrap().total_nanoseconds(),
+            Ok(-1_500_000_000)
+        );
+        assert_eq!(
+            parse("+PT1.5S").unwrap().total_nanoseconds(),
+            Ok(1_500_000_000)
+        );
+    }
+
+    #[test]
+    fn test_total_nanoseconds() {
+        assert_eq!(parse("PT0.000000001S").unwrap().total_nanoseconds(), Ok(1));
+        assert_eq!(
+            parse("P1DT0.5M").unwrap().total_nanoseconds(),
+            Ok(86_430_000_000_000)
+        );
+        assert_eq!(
+            parse("P0.5D").unwrap().total_nanoseconds(),
+            Ok(43_200_000_000_000)
+        );
+        assert!(parse("P1M").unwrap().total_nanoseconds().is_err());
+        assert!(parse("P1Y").unwrap().total_nanoseconds().is_err());
+    }
+
+    #[test]
+    fn test_parse_combined_weeks() {
+        let components = parse_with("P1W2DT3H", ParseOptions::LENIENT).unwrap();
+        assert_eq!(components.weeks, decimal(1, 0));
+        assert_eq!(components.days, decimal(2, 0));
+        assert_eq!(components.hours, decimal(3, 0));
+
+        let components = parse_with("P1Y2W", ParseOptions::LENIENT).unwrap();
+        assert_eq!(components.years, decimal(1, 0));
+        assert_eq!(components.weeks, decimal(2, 0));
+
+        assert_eq!(
+            parse_with("P2D1W", ParseOptions::LENIENT),
+            Err(ParseError::ComponentOutOfOrder { offset: 3 })
+        );
+        assert_eq!(parse("P1W2D"), Err(ParseError::WeeksNotAlone { offset: 3 }));
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        let strict = |input| parse_with(input, ParseOptions::STRICT);
+        assert!(strict("P1Y2M3DT4H5M6.5S").is_ok());
+        assert!(strict("P2W").is_ok());
+        assert_eq!(
+            strict("-P1D"),
+            Err(ParseError::UnexpectedChar {
+                offset: 0,
+                found: '-'
+            })
+        );
+        assert_eq!(
+            strict("+P1D"),
+            Err(ParseError::UnexpectedChar {
+                offset: 0,
+                found: '+'
+            })
+        );
+        assert_eq!(
+            strict("p1d"),
+            Err(ParseError::MissingDesignator { offset: 0 })
+        );
+        assert_eq!(strict("PT0,5S"), parse("PT0.5S"));
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let lenient = |input| parse_with(input, ParseOptions::LENIENT);
+        assert_eq!(lenient(" pt1h30m\n"), parse("PT1H30M"));
+        assert_eq!(lenient("-p1dt2h"), parse("-P1DT2H"));
+        assert_eq!(lenient("PT0,5S"), parse("PT0.5S"));
+
+        let components = lenient("P1.5DT2.25H").unwrap();
+        assert_eq!(components.days, decimal(1, 500_000_000));
+        assert_eq!(components.hours, decimal(2, 250_000_000));
+
+        // Offsets still point into the original input.
+        assert_eq!(
+            lenient("  P1X"),
+            Err(ParseError::UnexpectedChar {
+                offset: 4,
+                found: 'X'
+            })
+        );
+        assert_eq!(
+            lenient(" P "),
+            Err(ParseError::MissingComponent { offset: 2 })
+        );
+        assert_eq!(
+            lenient("P1D P"),
+            Err(ParseError::UnexpectedChar {
+                offset: 3,
+                found: ' '
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_component_signs() {
+        let lenient = |input| parse_with(input, ParseOptions::LENIENT);
+
+        let components = lenient("P1DT-2H").unwrap();
+        assert_eq!(components.days, decimal(1, 0));
+        assert_eq!(components.hours, decimal(2, 0));
+        assert!(components.negated[Unit::Hour as usize]);
+        assert!(!components.negated[Unit::Day as usize]);
+        assert_eq!(components.total_nanoseconds(), Ok(22 * 3_600_000_000_000));
+
+        assert_eq!(
+            lenient("PT1H-15M").unwrap().total_nanoseconds(),
+            Ok(45 * 60_000_000_000)
+        );
+        assert_eq!(
+            lenient("-PT1H-15M").unwrap().total_nanoseconds(),
+            Ok(-45 * 60_000_000_000)
+        );
+        assert_eq!(
+            lenient("PT-1H-0.5S").unwrap().total_nanoseconds(),
+            Ok(-3_600_500_000_000)
+        );
+        assert_eq!(
+            lenient("P-1M").unwrap().total_nanoseconds(),
+            Err(ParseError::CalendarUnitNotAllowed)
+        );
+        assert_eq!(
+            lenient("PT--1H"),
+            Err(ParseError::UnexpectedChar {
+                offset: 3,
+                found: '-'
+            })
+        );
+        assert_eq!(
+            lenient("PT+1H"),
+            Err(ParseError::UnexpectedChar {
+                offset: 2,
+                found: '+'
+            })
+        );
+        assert_eq!(
+            parse_with("P1DT-2H", ParseOptions::STRICT),
+            Err(ParseError::SignedComponent { offset: 4 })
+        );
+    }
+
+    #[test]
+    fn test_parse_alternative() {
+        for input in ["P0001-02-03T04:05:06.5", "P00010203T040506,5"] {
+            let components = parse(input).unwrap();
+            assert_eq!(components.years, decimal(1, 0));
+            assert_eq!(components.months, decimal(2, 0));
+            assert_eq!(components.days, decimal(3, 0));
+            assert_eq!(components.hours, decimal(4, 0));
+            assert_eq!(components.minutes, decimal(5, 0));
+            assert_eq!(components.seconds, decimal(6, 500_000_000));
+        }
+
+        assert!(parse("-P0000-00-01T00:00:00").unwrap().negative);
+        assert!(parse("P0000-00-00T00:00:00").unwrap().is_zero());
+        assert!(parse_with("P9999-11-29T23:59:59", ParseOptions::STRICT).is_ok());
+        assert_eq!(
+            parse_with(" p00000001t000000 ", ParseOptions::LENIENT),
+            parse("P00000001T000000")
+        );
+    }
+
+    #[test]
+    fn test_parse_alternative_invalid() {
+        use ParseError::*;
+
+        for (input, expected) in [
+            ("P0000-12-00T00:00:00", ComponentOutOfRange { offset: 6 }),
+            ("P0000-00-30T00:00:00", ComponentOutOfRange { offset: 9 }),
+            ("P0000-00-00T24:00:00", ComponentOutOfRange { offset: 12 }),
+            ("P0000-00-00T00:60:00", ComponentOutOfRange { offset: 15 }),
+            ("P00000000T000060", ComponentOutOfRange { offset: 14 }),
+            ("P0000-00-00", UnexpectedEnd { offset: 11 }),
+            (
+                "P0000-0-00T00:00:00",
+                UnexpectedChar {
+                    offset: 7,
+                    found: '-',
+                },
+            ),
+            ("P0000-00-00T00:00", UnexpectedEnd { offset: 17 }),
+            (
+                "P0000-00-00T000000",
+                UnexpectedChar {
+                    offset: 14,
+                    found: '0',
+                },
+            ),
+            (
+                "P00000000T00:00:00",
+                UnexpectedChar {
+                    offset: 12,
+                    found: ':',
+                },
+            ),
+            (
+                "P0000-00-00T00:00:00Z",
+                UnexpectedChar {
+                    offset: 20,
+                    found: 'Z',
+                },
+            ),
+            ("P0000-00-00T00:00:00.", UnexpectedEnd { offset: 21 }),
+            (
+                "P0000-00-00t00:00:00",
+                UnexpectedChar {
+                    offset: 11,
+                    found: 't',
+                },
+            ),
+        ] {
+            assert_eq!(parse(input), Err(expected), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        use ParseError::*;
+
+        for (input, expected) in [
+            ("", MissingDesignator { offset: 0 }),
+            ("P", MissingComponent { offset: 1 }),
+            ("PT", MissingComponent { offset: 2 }),
+            ("P1DT", MissingComponent { offset: 4 }),
+            ("1D", MissingDesignator { offset: 0 }),
+            ("P1", MissingDesignator { offset: 2 }),
+            (
+                "PD",
+                UnexpectedChar {
+                    offset: 1,
+                    found: 'D',
+                },
+            ),
+            ("P1H", MissingTimeDesignator { offset: 2 }),
+            ("P1M1S", MissingTimeDesignator { offset: 4 }),
+            (
+                "PT1D",
+                UnexpectedChar {
+                    offset: 3,
+                    found: 'D',
+                },
+            ),
+            (
+                "PT1HT1M",
+                UnexpectedChar {
+                    offset: 4,
+                    found: 'T',
+                },
+            ),
+            ("P1D1Y", ComponentOutOfOrder { offset: 3 }),
+            ("P1D1D", DuplicateComponent { offset: 3 }),
+            ("PT1M1M", DuplicateComponent { offset: 4 }),
+            ("PT1S1M", ComponentOutOfOrder { offset: 4 }),
+            ("P1.5DT1H", FractionNotOnLastComponent { offset: 1 }),
+            ("P1,5DT1H", FractionNotOnLastComponent { offset: 1 }),
+            (
+                "PT1,S",
+                UnexpectedChar {
+                    offset: 4,
+                    found: 'S',
+                },
+            ),
+            (
+                "PT1,.5S",
+                UnexpectedChar {
+                    offset: 4,
+                    found: '.',
+                },
+            ),
+            (
+                "PT1.S",
+                UnexpectedChar {
+                    offset: 4,
+                    found: 'S',
+                },
+            ),
+            ("PT1.", UnexpectedEnd { offset: 4 }),
+            (
+                "PT.5S",
+                UnexpectedChar {
+                    offset: 2,
+                    found: '.',
+                },
+            ),
+            ("PT0.0000000001S", TooManyFractionDigits { offset: 4 }),
+            ("P1W2D", WeeksNotAlone { offset: 3 }),
+            ("P1Y2W", WeeksNotAlone { offset: 3 }),
+            (
+                "P1DT1H ",
+                UnexpectedChar {
+                    offset: 6,
+                    found: ' ',
+                },
+            ),
+            ("P-1D", SignedComponent { offset: 1 }),
+            ("PT1H-15M", SignedComponent { offset: 4 }),
+            ("--P1D", MissingDesignator { offset: 1 }),
+            ("+-P1D", MissingDesignator { offset: 1 }),
+            ("-", MissingDesignator { offset: 1 }),
+            ("-P", MissingComponent { offset: 2 }),
+            (" -P1D", MissingDesignator { offset: 0 }),
+            (
+                "PT1Hé",
+                UnexpectedChar {
+                    offset: 4,
+                    found: 'é',
+                },
+            ),
+            ("PT18446744073709551616S", Overflow),
+        ] {
+            assert_eq!(parse(input), Err(expected), "{input:?}");
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Serialize and deserialize sequences of [`time::Duration`] using the ISO 8601 format.
+//!
+//! Works with any collection that can be iterated by reference and collected, such
+//! as [`Vec`], [`VecDeque`](std::collections::VecDeque) or
+//! [`BTreeSet`](std::collections::BTreeSet). Each element is written exactly like
+//! the crate's top-level functions write a single value.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Retry {
+//!     #[serde(with = "iso8601_duration_serde::seq")]
+//!     backoff: Vec<Duration>,
+//! }
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserializer, Serializer};
+use time::Duration;
+
+use crate::IsoDuration;
+
+/// Serialize a collection of [`time::Duration`] as a sequence of ISO 8601 strings.
+#[inline]
+pub fn serialize<S, C>(durations: &C, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    for<'c> &'c C: IntoIterator<Item = &'c Duration>,
+{
+    serializer.collect_seq(durations.into_iter().copied().map(IsoDuration))
+}
+
+/// Deserialize a collection of [`time::Duration`] from a sequence of ISO 8601 strings.
+#[inline]
+pub fn deserialize<'a, D, C>(deserializer: D) -> Result<C, D::Error>
+where
+    D: Deserializer<'a>,
+    C: FromIterator<Duration>,
+{
+    deserializer.deserialize_seq(SeqVisitor(PhantomData))
+}
+
+struct SeqVisitor<C>(PhantomData<C>);
+
+impl<'a, C: FromIterator<Duration>> Visitor<'a> for SeqVisitor<C> {
+    type Value = C;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of ISO 8601 durations")
+    }
+
+    fn visit_seq<A: SeqAccess<'a>>(self, mut seq: A) -> Result<C, A::Error> {
+        core::iter::from_fn(|| seq.next_element::<IsoDuration>().transpose())
+            .map(|element| element.map(|IsoDuration(duration)| duration))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, VecDeque};
+
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+    struct TestStruct {
+        #[serde(with = "super")]
+        vec: Vec<Duration>,
+        #[serde(with = "super")]
+        deque: VecDeque<Duration>,
+        #[serde(with = "super")]
+        set: BTreeSet<Duration>,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let test_struct = TestStruct {
+            vec: vec![
+                Duration::seconds(1),
+                Duration::seconds(2),
+                Duration::seconds(4),
+            ],
+            deque: VecDeque::from([Duration::minutes(-5)]),
+            set: BTreeSet::from([Duration::days(1), Duration::milliseconds(500)]),
+        };
+
+        let json = serde_json::to_string(&test_struct).unwrap();
+        assert_eq!(
+            json,
+            r#"{"vec":["PT1S","PT2S","PT4S"],"deque":["-PT5M"],"set":["PT0.5S","P1D"]}"#
+        );
+
+        let deserialized: TestStruct = serde_json::from_str(&json).unwrap();
+        assert_eq!(test_struct, deserialized);
+    }
+
+    #[test]
+    fn test_deserialize_invalid_element_is_error() {
+        let json = r#"{"vec":["PT1S","P1Y"],"deque":[],"set":[]}"#;
+        assert!(serde_json::from_str::<TestStruct>(json).is_err());
+    }
+}
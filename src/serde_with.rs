@@ -0,0 +1,161 @@
+use ::serde_with::{DeserializeAs, SerializeAs};
+use serde::{Deserializer, Serializer};
+
+/// Marker type for [`serde_with`](::serde_with) that (de)serializes durations using
+/// the ISO 8601 format.
+///
+/// Requires the `serde_with` feature. Supports [`time::Duration`] and
+/// [`std::time::Duration`], plus [`chrono::TimeDelta`](::chrono::TimeDelta) with the
+/// `chrono` feature and [`jiff::SignedDuration`](::jiff::SignedDuration) and
+/// [`jiff::Span`](::jiff::Span) with the `jiff` feature. Since it composes with
+/// `serde_with`'s own adapters, it also covers `Option`, collections and maps.
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use serde_with::serde_as;
+/// use time::Duration;
+/// use iso8601_duration_serde::Iso8601;
+///
+/// #[serde_as]
+/// #[derive(Serialize, Deserialize)]
+/// struct Retry {
+///     #[serde_as(as = "Option<Vec<Iso8601>>")]
+///     backoff: Option<Vec<Duration>>,
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Iso8601;
+
+impl SerializeAs<time::Duration> for Iso8601 {
+    fn serialize_as<S: Serializer>(
+        source: &time::Duration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        crate::serialize(source, serializer)
+    }
+}
+
+impl<'a> DeserializeAs<'a, time::Duration> for Iso8601 {
+    fn deserialize_as<D: Deserializer<'a>>(deserializer: D) -> Result<time::Duration, D::Error> {
+        crate::deserialize(deserializer)
+    }
+}
+
+impl SerializeAs<std::time::Duration> for Iso8601 {
+    fn serialize_as<S: Serializer>(
+        source: &std::time::Duration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        crate::std_time::serialize(source, serializer)
+    }
+}
+
+impl<'a> DeserializeAs<'a, std::time::Duration> for Iso8601 {
+    fn deserialize_as<D: Deserializer<'a>>(
+        deserializer: D,
+    ) -> Result<std::time::Duration, D::Error> {
+        crate::std_time::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl SerializeAs<::chrono::TimeDelta> for Iso8601 {
+    fn serialize_as<S: Serializer>(
+        source: &::chrono::TimeDelta,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        crate::chrono::serialize(source, serializer)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'a> DeserializeAs<'a, ::chrono::TimeDelta> for Iso8601 {
+    fn deserialize_as<D: Deserializer<'a>>(
+        deserializer: D,
+    ) -> Result<::chrono::TimeDelta, D::Error> {
+        crate::chrono::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl SerializeAs<::jiff::SignedDuration> for Iso8601 {
+    fn serialize_as<S: Serializer>(
+        source: &::jiff::SignedDuration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        crate::jiff::serialize(source, serializer)
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl<'a> DeserializeAs<'a, ::jiff::SignedDuration> for Iso8601 {
+    fn deserialize_as<D: Deserializer<'a>>(
+        deserializer: D,
+    ) -> Result<::jiff::SignedDuration, D::Error> {
+        crate::jiff::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl SerializeAs<::jiff::Span> for Iso8601 {
+    fn serialize_as<S: Serializer>(
+        source: &::jiff::Span,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        crate::jiff::span::serialize(source, serializer)
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl<'a> DeserializeAs<'a, ::jiff::Span> for Iso8601 {
+    fn deserialize_as<D: Deserializer<'a>>(deserializer: D) -> Result<::jiff::Span, D::Error> {
+        crate::jiff::span::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ::serde_with::serde_as;
+    use serde::{Deserialize, Serialize};
+
+    use super::Iso8601;
+
+    #[serde_as]
+    #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+    struct TestStruct {
+        #[serde_as(as = "Iso8601")]
+        duration: time::Duration,
+        #[serde_as(as = "Option<Vec<Iso8601>>")]
+        backoff: Option<Vec<time::Duration>>,
+        #[serde_as(as = "HashMap<_, Iso8601>")]
+        timeouts: HashMap<String, std::time::Duration>,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let test_struct = TestStruct {
+            duration: time::Duration::minutes(-30),
+            backoff: Some(vec![time::Duration::seconds(1), time::Duration::seconds(2)]),
+            timeouts: HashMap::from([("acme".to_owned(), std::time::Duration::from_millis(1500))]),
+        };
+
+        let json = serde_json::to_string(&test_struct).unwrap();
+        assert_eq!(
+            json,
+            r#"{"duration":"-PT30M","backoff":["PT1S","PT2S"],"timeouts":{"acme":"PT1.5S"}}"#
+        );
+
+        let deserialized: TestStruct = serde_json::from_str(&json).unwrap();
+        assert_eq!(test_struct, deserialized);
+    }
+
+    #[test]
+    fn test_none() {
+        let json = r#"{"duration":"PT0S","backoff":null,"timeouts":{}}"#;
+        let deserialized: TestStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.backoff, None);
+        assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+    }
+}
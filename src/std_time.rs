@@ -0,0 +1,131 @@
+//! Serialize and deserialize [`std::time::Duration`] using the ISO 8601 format.
+//!
+//! The output is identical to the crate's [`time::Duration`] functions. Since a
+//! [`std::time::Duration`] cannot be negative, signed inputs such as `-PT1S` are
+//! rejected.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use std::time::Duration;
+//!
+//! # #[cfg(feature = "serde")]
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "iso8601_duration_serde::std_time")]
+//!     timeout: Duration,
+//! }
+//! ```
+
+use core::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserializer, Serializer};
+use time_core::convert::*;
+
+use crate::components::Components;
+use crate::error::ParseError;
+
+/// Parse an [`std::time::Duration`] from its ISO 8601 representation.
+///
+/// Negative durations are rejected.
+#[inline]
+pub fn parse(input: &str) -> Result<Duration, ParseError> {
+    crate::parse::parse(input).and_then(from_components)
+}
+
+/// Format an [`std::time::Duration`] using the well-known ISO 8601 format.
+#[inline]
+pub fn format(duration: &Duration) -> String {
+    to_components(duration).to_string()
+}
+
+/// Display an [`std::time::Duration`] using the well-known ISO 8601 format.
+#[inline]
+pub fn display(duration: &Duration) -> impl fmt::Display + use<> {
+    to_components(duration)
+}
+
+/// Serialize an [`std::time::Duration`] using the well-known ISO 8601 format.
+#[cfg(feature = "serde")]
+#[inline]
+pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&to_components(duration))
+}
+
+/// Deserialize an [`std::time::Duration`] from its ISO 8601 representation.
+///
+/// Negative durations are rejected.
+#[cfg(feature = "serde")]
+#[inline]
+pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Duration, D::Error> {
+    crate::deserialize_with(deserializer, from_components)
+}
+
+pub(crate) fn to_components(duration: &Duration) -> Components {
+    Components::fixed(false, duration.as_secs(), duration.subsec_nanos())
+}
+
+pub(crate) fn from_components(components: Components) -> Result<Duration, ParseError> {
+    let nanoseconds = components.total_nanoseconds()?;
+    if nanoseconds < 0 {
+        return Err(ParseError::Negative);
+    }
+
+    let per_second = Nanosecond::per_t::<i128>(Second);
+    let seconds = u64::try_from(nanoseconds / per_second).map_err(|_| ParseError::Overflow)?;
+
+    Ok(Duration::new(seconds, (nanoseconds % per_second) as u32))
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+    struct TestStruct {
+        #[serde(with = "super")]
+        duration: Duration,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cases = [
+            (Duration::ZERO, "PT0S"),
+            (Duration::from_secs(45), "PT45S"),
+            (Duration::from_micros(250), "PT0.00025S"),
+            (
+                Duration::from_secs(2 * 86400 + 3 * 3600 + 30 * 60 + 15),
+                "P2DT3H30M15S",
+            ),
+            (Duration::MAX, "P213503982334601DT7H15.999999999S"),
+        ];
+
+        for (duration, expected) in cases {
+            let test_struct = TestStruct { duration };
+            let json = serde_json::to_string(&test_struct).unwrap();
+            assert_eq!(json, format!(r#"{{"duration":"{expected}"}}"#));
+
+            let deserialized: TestStruct = serde_json::from_str(&json).unwrap();
+            assert_eq!(test_struct, deserialized);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_negative_is_error() {
+        let json = r#"{"duration":"-PT1S"}"#;
+        let err = serde_json::from_str::<TestStruct>(json).unwrap_err();
+        assert!(err.to_string().contains("negative"), "{err}");
+
+        let json = r#"{"duration":"-PT0S"}"#;
+        let deserialized: TestStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.duration, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_deserialize_overflow_is_error() {
+        let json = r#"{"duration":"P213503982334601DT7H16S"}"#;
+        assert!(serde_json::from_str::<TestStruct>(json).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Serialize and deserialize [`time::Duration`], accepting only ISO 8601-1 durations.
+//!
+//! This is meant for inputs that must follow the standard exactly, such as a public
+//! API. It accepts what ISO 8601-1 and the duration grammar in RFC 3339 Appendix A
+//! allow, and rejects everything else with a specific [`ParseError`]:
+//!
+//! - no leading sign, and no sign on individual components
+//! - uppercase designators, with `T` before any hours, minutes or seconds
+//! - components in order, each at most once
+//! - weeks only on their own (`P2W`)
+//! - a fraction only on the last component, with `.` or `,` as the decimal sign
+//! - no whitespace
+//!
+//! ISO 8601-1 has no negative durations, so serializing one fails instead of writing
+//! a leading minus that this module would not read back. See the
+//! [`lenient`](crate::lenient) module for the opposite.
+//!
+//! ```
+//! use iso8601_duration_serde::ParseError;
+//! use serde::{Deserialize, Serialize};
+//! use time::Duration;
+//!
+//! # #[cfg(feature = "serde")]
+//! #[derive(Serialize, Deserialize)]
+//! struct Request {
+//!     #[serde(with = "iso8601_duration_serde::strict")]
+//!     timeout: Duration,
+//! }
+//!
+//! assert_eq!(iso8601_duration_serde::strict::parse("PT30S"), Ok(Duration::seconds(30)));
+//! assert_eq!(
+//!     iso8601_duration_serde::strict::parse("P1H"),
+//!     Err(ParseError::MissingTimeDesignator { offset: 2 })
+//! );
+//! ```
+
+use core::fmt;
+
+#[cfg(feature = "serde")]
+use serde::ser::Error as _;
+#[cfg(feature = "serde")]
+use serde::{Deserializer, Serializer};
+use time::Duration;
+
+use crate::error::ParseError;
+use crate::parse::ParseOptions;
+
+/// Parse a [`time::Duration`], accepting only the syntax listed in the
+/// [module documentation](self).
+#[inline]
+pub fn parse(input: &str) -> Result<Duration, ParseError> {
+    crate::parse::parse_with(input, ParseOptions::STRICT).and_then(crate::from_components)
+}
+
+/// Format a [`time::Duration`] using the well-known ISO 8601 format.
+///
+/// This is the same as [`crate::format`], so negative durations are written with a
+/// leading minus that [`parse`] rejects.
+#[inline]
+pub fn format(duration: &Duration) -> String {
+    crate::format(duration)
+}
+
+/// Display a [`time::Duration`] using the well-known ISO 8601 format.
+///
+/// This is the same as [`crate::display`], so negative durations are written with a
+/// leading minus that [`parse`] rejects.
+#[inline]
+pub fn display(duration: &Duration) -> impl fmt::Display + use<> {
+    crate::display(duration)
+}
+
+/// Serialize a [`time::Duration`] using the well-known ISO 8601 format.
+///
+/// Negative durations fail with [`ParseError::Negative`], so everything this writes
+/// can be read back by [`deserialize`].
+#[cfg(feature = "serde")]
+#[inline]
+pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    if duration.is_negative() {
+        return Err(S::Error::custom(ParseError::Negative));
+    }
+    crate::serialize(duration, serializer)
+}
+
+/// Deserialize a [`time::Duration`], accepting only the syntax listed in the
+/// [module documentation](self).
+#[cfg(feature = "serde")]
+#[inline]
+pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Duration, D::Error> {
+    crate::deserialize_with_options(deserializer, ParseOptions::STRICT, crate::from_components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("P1DT2H"), Ok(Duration::hours(26)));
+        assert_eq!(parse("PT0.5S"), Ok(Duration::milliseconds(500)));
+        assert_eq!(parse("PT0,5S"), Ok(Duration::milliseconds(500)));
+        assert_eq!(parse("P2W"), Ok(Duration::weeks(2)));
+    }
+
+    #[test]
+    fn test_parse_rejects_extensions() {
+        use ParseError::*;
+
+        for (input, expected) in [
+            (
+                "-PT1S",
+                UnexpectedChar {
+                    offset: 0,
+                    found: '-',
+                },
+            ),
+            (
+                "+PT1S",
+                UnexpectedChar {
+                    offset: 0,
+                    found: '+',
+                },
+            ),
+            ("pt1s", MissingDesignator { offset: 0 }),
+            (
+                "PT1s",
+                UnexpectedChar {
+                    offset: 3,
+                    found: 's',
+                },
+            ),
+            (" PT1S", MissingDesignator { offset: 0 }),
+            (
+                "PT1S ",
+                UnexpectedChar {
+                    offset: 4,
+                    found: ' ',
+                },
+            ),
+            ("P1.5DT1H", FractionNotOnLastComponent { offset: 1 }),
+            ("P1W2D", WeeksNotAlone { offset: 3 }),
+            ("P1H", MissingTimeDesignator { offset: 2 }),
+            ("PT1H1H", DuplicateComponent { offset: 4 }),
+            ("P1D1Y", ComponentOutOfOrder { offset: 3 }),
+            ("PT1H-15M", SignedComponent { offset: 4 }),
+        ] {
+            assert_eq!(parse(input), Err(expected), "{input:?}");
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "super")]
+            duration: Duration,
+        }
+
+        let json = r#"{"duration":"PT1M"}"#;
+        let deserialized: TestStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.duration, Duration::minutes(1));
+
+        let json = r#"{"duration":"-PT1M"}"#;
+        let err = serde_json::from_str::<TestStruct>(json).unwrap_err();
+        assert!(err.to_string().contains("at byte 0"), "{err}");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "super")]
+            duration: Duration,
+        }
+
+        for duration in [
+            Duration::ZERO,
+            Duration::minutes(1),
+            Duration::new(90_061, 500_000_000),
+        ] {
+            let json = serde_json::to_string(&TestStruct { duration }).unwrap();
+            let round_tripped: TestStruct = serde_json::from_str(&json).unwrap();
+            assert_eq!(round_tripped.duration, duration, "{json}");
+        }
+
+        let negative = TestStruct {
+            duration: Duration::minutes(-1),
+        };
+        let err = serde_json::to_string(&negative).unwrap_err();
+        assert_eq!(err.to_string(), ParseError::Negative.to_string());
+    }
+}
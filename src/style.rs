@@ -0,0 +1,168 @@
+//! Pick an output style per field with marker types.
+//!
+//! A [`Style`] is a type with [`FormatOptions`] attached as an associated constant.
+//! [`Styled`] turns it into something `#[serde(with = ...)]` accepts, so each field
+//! can be written in a different shape. Deserialization is the same as
+//! the crate's `deserialize` for every style.
+//!
+//! ```
+//! use iso8601_duration_serde::style::{Java, Style, Styled};
+//! use iso8601_duration_serde::FormatOptions;
+//! use serde::{Deserialize, Serialize};
+//! use time::Duration;
+//!
+//! struct Millis;
+//!
+//! impl Style for Millis {
+//!     const OPTIONS: FormatOptions = FormatOptions::new().fraction_digits(3);
+//! }
+//!
+//! # #[cfg(feature = "serde")]
+//! #[derive(Serialize, Deserialize)]
+//! struct Timeouts {
+//!     #[serde(with = "Styled::<Java>")]
+//!     session: Duration,
+//!     #[serde(with = "Styled::<Millis>")]
+//!     request: Duration,
+//! }
+//!
+//! assert_eq!(Styled::<Java>::format(&Duration::hours(36)), "PT36H");
+//! assert_eq!(Styled::<Millis>::format(&Duration::seconds(2)), "PT2.000S");
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+#[cfg(feature = "serde")]
+use serde::{Deserializer, Serializer};
+use time::Duration;
+
+use crate::components::Unit;
+use crate::error::ParseError;
+use crate::format::FormatOptions;
+
+/// A marker type carrying [`FormatOptions`].
+pub trait Style {
+    /// The options used to write durations in this style.
+    const OPTIONS: FormatOptions;
+}
+
+/// Hours, minutes and seconds without a day component, like Java's
+/// `Duration::toString`, e.g. `PT36H`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Java;
+
+impl Style for Java {
+    const OPTIONS: FormatOptions = FormatOptions::new().largest(Unit::Hour);
+}
+
+/// Weeks when the value is an exact number of weeks, e.g. `P2W`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Weeks;
+
+impl Style for Weeks {
+    const OPTIONS: FormatOptions = FormatOptions::new().weeks(true);
+}
+
+/// A comma as the decimal sign, e.g. `PT1,5S`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Comma;
+
+impl Style for Comma {
+    const OPTIONS: FormatOptions = FormatOptions::new().decimal_comma(true);
+}
+
+/// Parses, formats, serializes and deserializes a [`time::Duration`], writing it in
+/// the style `S`.
+///
+/// Use it with `#[serde(with = "Styled::<Java>")]`.
+pub struct Styled<S>(PhantomData<S>);
+
+impl<S: Style> Styled<S> {
+    /// Parse a [`time::Duration`] from its ISO 8601 representation.
+    ///
+    /// This is the same as [`crate::parse`].
+    #[inline]
+    pub fn parse(input: &str) -> Result<Duration, ParseError> {
+        crate::parse(input)
+    }
+
+    /// Format a [`time::Duration`] in the style `S`.
+    #[inline]
+    pub fn format(duration: &Duration) -> String {
+        S::OPTIONS.format(duration)
+    }
+
+    /// Display a [`time::Duration`] in the style `S`.
+    #[inline]
+    pub fn display(duration: &Duration) -> impl fmt::Display + use<S> {
+        S::OPTIONS.display(duration)
+    }
+
+    /// Serialize a [`time::Duration`] in the style `S`.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn serialize<Ser: Serializer>(
+        duration: &Duration,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_str(&S::OPTIONS.display(duration))
+    }
+
+    /// Deserialize a [`time::Duration`] from its ISO 8601 representation.
+    ///
+    /// This is the same as [`crate::deserialize`].
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Duration, D::Error> {
+        crate::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_styles() {
+        let duration = Duration::weeks(2);
+        assert_eq!(Styled::<Java>::format(&duration), "PT336H");
+        assert_eq!(Styled::<Weeks>::format(&duration), "P2W");
+        assert_eq!(Styled::<Weeks>::display(&duration).to_string(), "P2W");
+        assert_eq!(Styled::<Java>::parse("PT336H"), Ok(duration));
+        assert_eq!(
+            Styled::<Comma>::format(&Duration::milliseconds(1_500)),
+            "PT1,5S"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde::{Deserialize, Serialize};
+
+        struct Compact;
+
+        impl Style for Compact {
+            const OPTIONS: FormatOptions = FormatOptions::new()
+                .max_components(1)
+                .zero(crate::ZeroStyle::Days);
+        }
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "Styled::<Java>")]
+            java: Duration,
+            #[serde(with = "Styled::<Compact>")]
+            compact: Duration,
+        }
+
+        let value = TestStruct {
+            java: Duration::hours(36),
+            compact: Duration::ZERO,
+        };
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, r#"{"java":"PT36H","compact":"P0D"}"#);
+        assert_eq!(serde_json::from_str::<TestStruct>(&json).unwrap(), value);
+    }
+}
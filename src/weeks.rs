@@ -0,0 +1,105 @@
+//! Serialize [`time::Duration`] using the week designator when possible.
+//!
+//! A duration that is an exact, non-zero number of weeks is written as `PnW`, e.g.
+//! `P2W` instead of `P14D`. Anything else is written like the crate's `serialize`.
+//! Deserialization is the same as the crate's `deserialize`, which reads `PnW` too.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use time::Duration;
+//!
+//! # #[cfg(feature = "serde")]
+//! #[derive(Serialize, Deserialize)]
+//! struct Schedule {
+//!     #[serde(with = "iso8601_duration_serde::weeks")]
+//!     cadence: Duration,
+//! }
+//!
+//! assert_eq!(iso8601_duration_serde::weeks::format(&Duration::weeks(2)), "P2W");
+//! assert_eq!(iso8601_duration_serde::weeks::format(&Duration::days(10)), "P10D");
+//! ```
+
+use core::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserializer, Serializer};
+use time::Duration;
+
+use crate::error::ParseError;
+use crate::style::{Styled, Weeks};
+
+/// Parse a [`time::Duration`] from its ISO 8601 representation.
+///
+/// This is the same as [`crate::parse`].
+#[inline]
+pub fn parse(input: &str) -> Result<Duration, ParseError> {
+    crate::parse(input)
+}
+
+/// Format a [`time::Duration`], using weeks if it is an exact number of weeks.
+#[inline]
+pub fn format(duration: &Duration) -> String {
+    Styled::<Weeks>::format(duration)
+}
+
+/// Display a [`time::Duration`], using weeks if it is an exact number of weeks.
+#[inline]
+pub fn display(duration: &Duration) -> impl fmt::Display + use<> {
+    Styled::<Weeks>::display(duration)
+}
+
+/// Serialize a [`time::Duration`], using weeks if it is an exact number of weeks.
+#[cfg(feature = "serde")]
+#[inline]
+pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    Styled::<Weeks>::serialize(duration, serializer)
+}
+
+/// Deserialize a [`time::Duration`] from its ISO 8601 representation.
+///
+/// This is the same as [`crate::deserialize`].
+#[cfg(feature = "serde")]
+#[inline]
+pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Duration, D::Error> {
+    crate::deserialize(deserializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format(&Duration::weeks(2)), "P2W");
+        assert_eq!(format(&Duration::weeks(-3)), "-P3W");
+        assert_eq!(format(&Duration::days(10)), "P10D");
+        assert_eq!(format(&(Duration::weeks(1) + Duration::hours(1))), "P7DT1H");
+        assert_eq!(format(&Duration::ZERO), "PT0S");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for duration in [Duration::weeks(1), Duration::weeks(52), Duration::days(9)] {
+            assert_eq!(parse(&format(&duration)), Ok(duration));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "super")]
+            duration: Duration,
+        }
+
+        let value = TestStruct {
+            duration: Duration::weeks(2),
+        };
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, r#"{"duration":"P2W"}"#);
+        assert_eq!(serde_json::from_str::<TestStruct>(&json).unwrap(), value);
+    }
+}
@@ -0,0 +1,524 @@
+//! Serialize and deserialize the XML Schema 1.1 duration types.
+//!
+//! Each type has its own module, to use with `#[serde(with = ...)]`:
+//!
+//! - [`duration`]: `xs:duration`, as a [`CalendarDuration`](crate::CalendarDuration)
+//! - [`day_time_duration`]: `xs:dayTimeDuration`, as a [`time::Duration`]
+//! - [`year_month_duration`]: `xs:yearMonthDuration`, as a
+//!   [`CalendarDuration`](crate::CalendarDuration) with only years and months
+//!
+//! They follow the XSD lexical rules, which differ from ISO 8601 in places:
+//!
+//! - a leading `-` is allowed, but not `+`
+//! - there are no weeks
+//! - only the seconds may have a fraction, written with `.`
+//! - there is at least one component, and `T` must be followed by one
+//! - the alternative format (`P0001-02-03T04:05:06`) is not allowed
+//!
+//! Spaces, tabs and line breaks around the value are accepted, since XSD collapses
+//! them before checking the lexical rules. Other Unicode whitespace is rejected. XSD puts no limit on the fractional digits of the seconds, so
+//! any number is accepted, but only nanoseconds are kept and the rest is truncated.
+//!
+//! ```
+//! use iso8601_duration_serde::CalendarDuration;
+//! use serde::{Deserialize, Serialize};
+//! use time::Duration;
+//!
+//! # #[cfg(feature = "serde")]
+//! #[derive(Serialize, Deserialize)]
+//! struct Order {
+//!     #[serde(with = "iso8601_duration_serde::xsd::duration")]
+//!     warranty: CalendarDuration,
+//!     #[serde(with = "iso8601_duration_serde::xsd::day_time_duration")]
+//!     processing: Duration,
+//! }
+//!
+//! use iso8601_duration_serde::xsd;
+//! assert_eq!(xsd::day_time_duration::parse("P1DT2H"), Ok(Duration::hours(26)));
+//! assert!(xsd::day_time_duration::parse("P1Y").is_err());
+//! assert!(xsd::duration::parse("P2W").is_err());
+//! ```
+
+use crate::parse::ParseOptions;
+
+/// The lexical rules shared by every XSD duration type. Each type narrows down the
+/// units.
+const OPTIONS: ParseOptions = ParseOptions {
+    sign: true,
+    whitespace: true,
+    xsd: true,
+    ..ParseOptions::STRICT
+};
+
+pub mod duration {
+    //! `xs:duration`, as a [`CalendarDuration`].
+    //!
+    //! See the [parent module](super) for the lexical rules. Weeks are written as
+    //! days, since XSD has no weeks.
+
+    #[cfg(feature = "serde")]
+    use serde::ser::Error as _;
+    #[cfg(feature = "serde")]
+    use serde::{Deserializer, Serializer};
+
+    use super::OPTIONS;
+    use crate::calendar::{self, CalendarDuration};
+    #[cfg(feature = "serde")]
+    use crate::components::Decimal;
+    use crate::components::Unit;
+    use crate::error::ParseError;
+    use crate::parse::ParseOptions;
+
+    const DURATION: ParseOptions = ParseOptions {
+        units: &[
+            Unit::Year,
+            Unit::Month,
+            Unit::Day,
+            Unit::Hour,
+            Unit::Minute,
+            Unit::Second,
+        ],
+        ..OPTIONS
+    };
+
+    /// Parse an `xs:duration`.
+    #[inline]
+    pub fn parse(input: &str) -> Result<CalendarDuration, ParseError> {
+        crate::parse::parse_with(input, DURATION).and_then(calendar::from_components)
+    }
+
+    /// Serialize a [`CalendarDuration`] as an `xs:duration`.
+    ///
+    /// Fails if the weeks and days together are too large to write as days.
+    #[cfg(feature = "serde")]
+    pub fn serialize<S: Serializer>(
+        duration: &CalendarDuration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut components = calendar::to_components(duration);
+        let days = components
+            .weeks
+            .whole
+            .checked_mul(7)
+            .and_then(|days| days.checked_add(components.days.whole))
+            .ok_or_else(|| S::Error::custom("duration is too large for xs:duration"))?;
+        components.weeks = Decimal::default();
+        components.days = Decimal::whole(days);
+        serializer.collect_str(&components)
+    }
+
+    /// Deserialize an `xs:duration`.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn deserialize<'a, D: Deserializer<'a>>(
+        deserializer: D,
+    ) -> Result<CalendarDuration, D::Error> {
+        crate::deserialize_with_options(deserializer, DURATION, calendar::from_components)
+    }
+}
+
+pub mod day_time_duration {
+    //! `xs:dayTimeDuration`, as a [`time::Duration`].
+    //!
+    //! See the [parent module](super) for the lexical rules. Only days, hours, minutes
+    //! and seconds may appear.
+
+    use core::fmt;
+
+    #[cfg(feature = "serde")]
+    use serde::{Deserializer, Serializer};
+    use time::Duration;
+
+    use super::OPTIONS;
+    use crate::components::Unit;
+    use crate::error::ParseError;
+    use crate::parse::ParseOptions;
+
+    const DAY_TIME_DURATION: ParseOptions = ParseOptions {
+        units: &[Unit::Day, Unit::Hour, Unit::Minute, Unit::Second],
+        ..OPTIONS
+    };
+
+    /// Parse an `xs:dayTimeDuration`.
+    #[inline]
+    pub fn parse(input: &str) -> Result<Duration, ParseError> {
+        crate::parse::parse_with(input, DAY_TIME_DURATION).and_then(crate::from_components)
+    }
+
+    /// Format a [`time::Duration`] as an `xs:dayTimeDuration`.
+    ///
+    /// This is the same as [`crate::format`].
+    #[inline]
+    pub fn format(duration: &Duration) -> String {
+        crate::format(duration)
+    }
+
+    /// Display a [`time::Duration`] as an `xs:dayTimeDuration`.
+    ///
+    /// This is the same as [`crate::display`].
+    #[inline]
+    pub fn display(duration: &Duration) -> impl fmt::Display + use<> {
+        crate::display(duration)
+    }
+
+    /// Serialize a [`time::Duration`] as an `xs:dayTimeDuration`.
+    ///
+    /// This is the same as [`crate::serialize`].
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::serialize(duration, serializer)
+    }
+
+    /// Deserialize an `xs:dayTimeDuration`.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Duration, D::Error> {
+        crate::deserialize_with_options(deserializer, DAY_TIME_DURATION, crate::from_components)
+    }
+}
+
+pub mod year_month_duration {
+    //! `xs:yearMonthDuration`, as a [`CalendarDuration`] with only years and months.
+    //!
+    //! See the [parent module](super) for the lexical rules. Only years and months may
+    //! appear.
+
+    #[cfg(feature = "serde")]
+    use serde::ser::Error as _;
+    #[cfg(feature = "serde")]
+    use serde::{Deserializer, Serializer};
+
+    use super::OPTIONS;
+    use crate::calendar::{self, CalendarDuration};
+    use crate::components::Unit;
+    use crate::error::ParseError;
+    use crate::parse::ParseOptions;
+
+    const YEAR_MONTH_DURATION: ParseOptions = ParseOptions {
+        units: &[Unit::Year, Unit::Month],
+        ..OPTIONS
+    };
+
+    /// Parse an `xs:yearMonthDuration`.
+    #[inline]
+    pub fn parse(input: &str) -> Result<CalendarDuration, ParseError> {
+        crate::parse::parse_with(input, YEAR_MONTH_DURATION).and_then(calendar::from_components)
+    }
+
+    /// Serialize a [`CalendarDuration`] as an `xs:yearMonthDuration`, e.g. `P1Y2M`.
+    ///
+    /// Zero is written as `P0M`. Fails if the duration has weeks, days or a time part.
+    #[cfg(feature = "serde")]
+    pub fn serialize<S: Serializer>(
+        duration: &CalendarDuration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let years_and_months = CalendarDuration::new(
+            duration.years(),
+            duration.months(),
+            0,
+            0,
+            time::Duration::ZERO,
+        );
+        if years_and_months != Ok(*duration) {
+            return Err(S::Error::custom(
+                "xs:yearMonthDuration can only hold years and months",
+            ));
+        }
+
+        if duration.is_zero() {
+            serializer.serialize_str("P0M")
+        } else {
+            serializer.collect_str(duration)
+        }
+    }
+
+    /// Deserialize an `xs:yearMonthDuration`.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn deserialize<'a, D: Deserializer<'a>>(
+        deserializer: D,
+    ) -> Result<CalendarDuration, D::Error> {
+        crate::deserialize_with_options(
+            deserializer,
+            YEAR_MONTH_DURATION,
+            calendar::from_components,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Duration;
+
+    use super::*;
+    use crate::CalendarDuration;
+    use crate::error::ParseError;
+
+    #[test]
+    fn test_duration_valid() {
+        for (input, expected) in [
+            ("P1Y2M3DT10H30M", "P1Y2M3DT10H30M"),
+            ("-P120D", "-P120D"),
+            ("P1347Y", "P1347Y"),
+            ("P1347M", "P1347M"),
+            ("P1Y2MT2H", "P1Y2MT2H"),
+            ("P0Y1347M", "P1347M"),
+            ("P0Y1347M0D", "P1347M"),
+            ("-P1347M", "-P1347M"),
+            ("PT1.5S", "PT1.5S"),
+            ("PT0S", "PT0S"),
+            ("-P0D", "PT0S"),
+            ("P1DT2H3M4.000000001S", "P1DT2H3M4.000000001S"),
+            ("PT0.1234567891S", "PT0.123456789S"),
+            ("PT1.0000000009999S", "PT1S"),
+            (" P1D\n", "P1D"),
+        ] {
+            let parsed = duration::parse(input);
+            assert_eq!(
+                parsed.map(|duration| duration.to_string()),
+                Ok(expected.to_owned()),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_duration_invalid() {
+        use ParseError::*;
+
+        for (input, expected) in [
+            ("P-1347M", SignedComponent { offset: 1 }),
+            ("P1Y2MT", MissingComponent { offset: 6 }),
+            ("P", MissingComponent { offset: 1 }),
+            ("PT", MissingComponent { offset: 2 }),
+            ("1Y", MissingDesignator { offset: 0 }),
+            ("P1.5Y", FractionNotOnSeconds { offset: 1 }),
+            ("PT1.5H", FractionNotOnSeconds { offset: 2 }),
+            ("P1M2Y", ComponentOutOfOrder { offset: 3 }),
+            ("P1Y1Y", DuplicateComponent { offset: 3 }),
+            (" ", MissingDesignator { offset: 0 }),
+            ("\r\n", MissingDesignator { offset: 0 }),
+            ("\u{3000}P1D", MissingDesignator { offset: 0 }),
+            (
+                "P1D\u{a0}",
+                UnexpectedChar {
+                    offset: 3,
+                    found: '\u{a0}',
+                },
+            ),
+            ("P1S", MissingTimeDesignator { offset: 2 }),
+            (
+                "PT1.S",
+                UnexpectedChar {
+                    offset: 4,
+                    found: 'S',
+                },
+            ),
+            (
+                "PT.5S",
+                UnexpectedChar {
+                    offset: 2,
+                    found: '.',
+                },
+            ),
+            ("p1d", MissingDesignator { offset: 0 }),
+            (
+                "P1d",
+                UnexpectedChar {
+                    offset: 2,
+                    found: 'd',
+                },
+            ),
+            (
+                "+P1D",
+                UnexpectedChar {
+                    offset: 0,
+                    found: '+',
+                },
+            ),
+            (
+                "P2W",
+                UnexpectedChar {
+                    offset: 2,
+                    found: 'W',
+                },
+            ),
+            (
+                "PT1,5S",
+                UnexpectedChar {
+                    offset: 3,
+                    found: ',',
+                },
+            ),
+            (
+                "P0001-02-03T04:05:06",
+                UnexpectedChar {
+                    offset: 5,
+                    found: '-',
+                },
+            ),
+            (
+                "P1D T1H",
+                UnexpectedChar {
+                    offset: 3,
+                    found: ' ',
+                },
+            ),
+        ] {
+            assert_eq!(duration::parse(input), Err(expected), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_day_time_duration() {
+        for (input, expected) in [
+            ("P3DT10H30M", Duration::days(3) + Duration::minutes(630)),
+            ("PT0S", Duration::ZERO),
+            ("P1D", Duration::days(1)),
+            ("PT36H", Duration::hours(36)),
+            ("-PT1.5S", Duration::milliseconds(-1_500)),
+            ("P0DT1M", Duration::minutes(1)),
+            ("PT0.1234567891S", Duration::nanoseconds(123_456_789)),
+        ] {
+            assert_eq!(day_time_duration::parse(input), Ok(expected), "{input:?}");
+        }
+
+        for (input, expected) in [
+            (
+                "P1Y",
+                ParseError::UnexpectedChar {
+                    offset: 2,
+                    found: 'Y',
+                },
+            ),
+            (
+                "P0M1D",
+                ParseError::UnexpectedChar {
+                    offset: 2,
+                    found: 'M',
+                },
+            ),
+            (
+                "P1W",
+                ParseError::UnexpectedChar {
+                    offset: 2,
+                    found: 'W',
+                },
+            ),
+            ("P1.5D", ParseError::FractionNotOnSeconds { offset: 1 }),
+            ("P", ParseError::MissingComponent { offset: 1 }),
+            (" ", ParseError::MissingDesignator { offset: 0 }),
+            ("\t", ParseError::MissingDesignator { offset: 0 }),
+            ("\n", ParseError::MissingDesignator { offset: 0 }),
+            ("\u{3000}P1D", ParseError::MissingDesignator { offset: 0 }),
+        ] {
+            assert_eq!(day_time_duration::parse(input), Err(expected), "{input:?}");
+        }
+        assert_eq!(
+            day_time_duration::parse("\r\n\tP1D "),
+            Ok(Duration::days(1))
+        );
+
+        assert_eq!(day_time_duration::format(&Duration::hours(-26)), "-P1DT2H");
+    }
+
+    #[test]
+    fn test_year_month_duration() {
+        for (input, expected) in [
+            (
+                "P1Y2M",
+                CalendarDuration::new(1, 2, 0, 0, Duration::ZERO).unwrap(),
+            ),
+            ("P14M", CalendarDuration::from_months(14)),
+            ("-P1Y", CalendarDuration::from_years(-1)),
+            ("P0M", CalendarDuration::ZERO),
+        ] {
+            assert_eq!(year_month_duration::parse(input), Ok(expected), "{input:?}");
+        }
+
+        for (input, expected) in [
+            (
+                "P1D",
+                ParseError::UnexpectedChar {
+                    offset: 2,
+                    found: 'D',
+                },
+            ),
+            (
+                "P1Y1D",
+                ParseError::UnexpectedChar {
+                    offset: 4,
+                    found: 'D',
+                },
+            ),
+            (
+                "PT1H",
+                ParseError::UnexpectedChar {
+                    offset: 3,
+                    found: 'H',
+                },
+            ),
+            ("P1.5Y", ParseError::FractionNotOnSeconds { offset: 1 }),
+            ("P1Y2MT", ParseError::MissingComponent { offset: 6 }),
+            ("\t", ParseError::MissingDesignator { offset: 0 }),
+        ] {
+            assert_eq!(
+                year_month_duration::parse(input),
+                Err(expected),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "duration")]
+            duration: CalendarDuration,
+            #[serde(with = "day_time_duration")]
+            day_time: Duration,
+            #[serde(with = "year_month_duration")]
+            year_month: CalendarDuration,
+        }
+
+        let json = r#"{"duration":"P1Y2M3DT4H","day_time":"P1DT2H","year_month":"P0M"}"#;
+        let value: TestStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(value.day_time, Duration::hours(26));
+        assert_eq!(serde_json::to_string(&value).unwrap(), json);
+
+        let weeks = TestStruct {
+            duration: "P1W2D".parse().unwrap(),
+            day_time: Duration::weeks(1),
+            year_month: CalendarDuration::from_years(-2),
+        };
+        assert_eq!(
+            serde_json::to_string(&weeks).unwrap(),
+            r#"{"duration":"P9D","day_time":"P7D","year_month":"-P2Y"}"#
+        );
+
+        let days = TestStruct {
+            year_month: CalendarDuration::from_days(1),
+            ..weeks
+        };
+        let err = serde_json::to_string(&days).unwrap_err();
+        assert!(
+            err.to_string().contains("can only hold years and months"),
+            "{err}"
+        );
+
+        for json in [
+            r#"{"duration":"P2W","day_time":"P1D","year_month":"P1Y"}"#,
+            r#"{"duration":" ","day_time":"P1D","year_month":"P1Y"}"#,
+            r#"{"duration":"P1D","day_time":"\n","year_month":"P1Y"}"#,
+            r#"{"duration":"P1D","day_time":"P1D","year_month":"\t"}"#,
+        ] {
+            assert!(serde_json::from_str::<TestStruct>(json).is_err(), "{json}");
+        }
+    }
+}